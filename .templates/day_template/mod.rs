@@ -0,0 +1,28 @@
+// To use, copy this directory to src/lib/days/dayN, then add `mod dayN;` and `day!(N, dayN)` to
+// DAYS in src/lib/days/mod.rs so the runner can find it.
+use crate::days::Solution;
+
+pub struct Puzzle {}
+
+impl Solution for Puzzle {
+    fn parse(_input: &str, _sample: bool) -> Self {
+        Puzzle {}
+    }
+
+    fn part1(&self) -> String {
+        part1().to_string()
+    }
+
+    // fn part2(&self) -> Option<String> {
+    //     Some(part2().to_string())
+    // }
+}
+
+fn part1() -> u64 {
+    todo!()
+}
+
+#[allow(dead_code)]
+fn part2() -> u64 {
+    todo!()
+}
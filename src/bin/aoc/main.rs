@@ -0,0 +1,139 @@
+use std::process;
+
+use anyhow::Result;
+use lib::bench::{self, format_duration, DayBench};
+use lib::common_startup::{startup, CheckSummary, Cli, Command};
+use lib::days::{self, Day, ExpectedAnswers, DAYS};
+use log::{debug, error, info, warn};
+
+fn main() {
+    let cli = startup();
+
+    if cli.check && cli.input.is_some() {
+        error!("--check compares against recorded answers, so can't be used with --input");
+        process::exit(1);
+    }
+
+    match &cli.command {
+        Command::Run { day, part } => {
+            let days_to_run = select_days(&cli, *day);
+
+            let mut failed = false;
+            let mut summary = CheckSummary::default();
+            for day in days_to_run {
+                if let Err(err) = run_day(&cli, day, *part, &mut summary) {
+                    error!("{:#}", err);
+                    failed = true;
+                }
+            }
+            if cli.check {
+                summary.report();
+                failed |= !summary.all_passed();
+            }
+            if failed {
+                process::exit(1);
+            }
+        }
+        Command::Bench { day, runs, json } => {
+            if cli.check {
+                error!("--check can't be used when benchmarking");
+                process::exit(1);
+            }
+            if cli.time {
+                error!("--time can't be used when benchmarking, which always reports times");
+                process::exit(1);
+            }
+
+            let mut failed = false;
+            let mut benches = vec![];
+            for day in select_days(&cli, *day) {
+                match bench_day(&cli, day, *runs) {
+                    Ok(day_bench) => benches.push(day_bench),
+                    Err(err) => {
+                        error!("{:#}", err);
+                        failed = true;
+                    }
+                }
+            }
+            if *json {
+                println!("{}", serde_json::to_string_pretty(&benches).unwrap());
+            } else {
+                info!("{}", bench::table(&benches).trim_end());
+            }
+            if failed {
+                process::exit(1);
+            }
+        }
+    }
+}
+
+fn select_days(cli: &Cli, day: Option<u8>) -> Vec<&'static Day> {
+    match day {
+        Some(number) => match days::get(number) {
+            Some(day) => vec![day],
+            None => {
+                error!("No solution for day {}", number);
+                process::exit(1);
+            }
+        },
+        None if cli.input.is_some() => {
+            error!("--input needs a single day to run against");
+            process::exit(1);
+        }
+        None => DAYS.iter().collect(),
+    }
+}
+
+fn run_day(cli: &Cli, day: &Day, part: Option<u8>, summary: &mut CheckSummary) -> Result<()> {
+    info!("Day {}", day.number);
+
+    let input = cli.read_input(day)?;
+    let expected = if cli.check {
+        day.expected_answers(cli.sample)?
+    } else {
+        ExpectedAnswers::default()
+    };
+    let (solution, parse_time) = bench::time(|| day.solution(&input, cli.sample));
+    let mut timings = vec![format!("parse {}", format_duration(parse_time))];
+
+    if part != Some(2) {
+        let (answer, part1_time) = bench::time(|| solution.part1());
+        timings.push(format!("part1 {}", format_duration(part1_time)));
+        info!("Part1: {}", format_answer(&answer));
+        if cli.check {
+            summary.check(day.number, 1, Some(&answer), expected.part1.as_deref());
+        }
+    }
+    if part != Some(1) {
+        let (answer, part2_time) = bench::time(|| solution.part2());
+        if answer.is_some() {
+            timings.push(format!("part2 {}", format_duration(part2_time)));
+        }
+        match &answer {
+            Some(answer) => info!("Part2: {}", format_answer(answer)),
+            None => warn!("Part2: not solved yet"),
+        }
+        if cli.check {
+            summary.check(day.number, 2, answer.as_deref(), expected.part2.as_deref());
+        }
+    }
+    if cli.time {
+        info!("Time: {}", timings.join(", "));
+    }
+    Ok(())
+}
+
+fn bench_day(cli: &Cli, day: &Day, runs: u32) -> Result<DayBench> {
+    debug!("Benchmarking day {}", day.number);
+    let input = cli.read_input(day)?;
+    Ok(bench::bench_day(day, &input, cli.sample, runs))
+}
+
+// Multi-line answers (e.g. rendered text) read better starting on their own line.
+fn format_answer(answer: &str) -> String {
+    if answer.contains('\n') {
+        format!("\n{}", answer)
+    } else {
+        answer.to_owned()
+    }
+}
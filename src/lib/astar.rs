@@ -0,0 +1,98 @@
+use std::ops::{Add, Sub};
+
+use num::ToPrimitive;
+
+use crate::dijkstra::Solver;
+use crate::geom::Point2;
+use crate::graph::Graph;
+use crate::grid::Pos;
+
+// Same shape as dijkstra::solve, but guided towards end by heuristic, which estimates the remaining
+// distance from a node and must never overestimate it.
+pub fn solve<G, H>(
+    graph: &G,
+    start: G::Node,
+    end: G::Node,
+    heuristic: H,
+) -> Option<(Vec<G::Node>, u64)>
+where
+    G: Graph<Cost = u64>,
+    H: Fn(&G::Node) -> u64,
+{
+    let mut solver = Solver::new(start);
+    let length = solver.solve_with_heuristic(graph, &end, heuristic)?;
+    Some((solver.path_to(&end).unwrap(), length))
+}
+
+// As solve, but only finds the length of the shortest route.
+pub fn distance<G, H>(graph: &G, start: G::Node, end: G::Node, heuristic: H) -> Option<u64>
+where
+    G: Graph<Cost = u64>,
+    H: Fn(&G::Node) -> u64,
+{
+    Solver::new(start).solve_with_heuristic(graph, &end, heuristic)
+}
+
+// Heuristic for grids where each orthogonal step costs at least 1.
+pub fn manhattan(end: Pos) -> impl Fn(&Pos) -> u64 {
+    move |&(row, col)| (row.abs_diff(end.0) + col.abs_diff(end.1)) as u64
+}
+
+// As manhattan, for Point2 coordinates.
+pub fn manhattan_point<T>(end: Point2<T>) -> impl Fn(&Point2<T>) -> u64
+where
+    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + ToPrimitive,
+{
+    move |point| point.manhattan(&end).to_u64().unwrap()
+}
+
+// Heuristic for when diagonal steps are allowed, each costing at least 1.
+pub fn chebyshev_point<T>(end: Point2<T>) -> impl Fn(&Point2<T>) -> u64
+where
+    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + ToPrimitive,
+{
+    move |point| point.chebyshev(&end).to_u64().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dijkstra;
+    use crate::graph;
+    use crate::grid::Grid;
+
+    #[test]
+    fn grid_maze() {
+        let maze = Grid::parse(
+            "\
+.....
+.###.
+...#.
+.#...",
+            |c| c == '#',
+        )
+        .unwrap();
+        let open = graph::from_fn(|pos: &Pos| {
+            maze.neighbours4(*pos)
+                .filter(|next| !maze[*next])
+                .map(|next| (next, 1))
+                .collect::<Vec<_>>()
+        });
+
+        let (path, length) = solve(&open, (3, 0), (3, 4), manhattan((3, 4))).unwrap();
+        assert_eq!(6, length);
+        assert_eq!(7, path.len());
+        assert_eq!(
+            dijkstra::distance(&open, (3, 0), (0, 4)),
+            distance(&open, (3, 0), (0, 4), manhattan((0, 4)))
+        );
+        assert_eq!(None, distance(&open, (3, 0), (1, 1), manhattan((1, 1))));
+    }
+
+    #[test]
+    fn point_heuristics() {
+        let end = Point2::new(3, -4);
+        assert_eq!(7, manhattan_point(end)(&Point2::new(0, 0)));
+        assert_eq!(4, chebyshev_point(end)(&Point2::new(0, 0)));
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    fmt::Write,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use crate::days::Day;
+use serde::{Serialize, Serializer};
+
+// Run f, returning its result along with how long it took.
+pub fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_secs_f64() * 1_000_000.0;
+    if micros < 1_000.0 {
+        format!("{:.1}µs", micros)
+    } else if micros < 1_000_000.0 {
+        format!("{:.2}ms", micros / 1_000.0)
+    } else {
+        format!("{:.2}s", micros / 1_000_000.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Stats {
+    #[serde(rename = "min_us", serialize_with = "as_micros")]
+    pub min: Duration,
+    #[serde(rename = "median_us", serialize_with = "as_micros")]
+    pub median: Duration,
+    #[serde(rename = "max_us", serialize_with = "as_micros")]
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut samples = samples.to_vec();
+        samples.sort_unstable();
+
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+
+        Some(Stats {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        })
+    }
+}
+
+fn as_micros<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64() * 1_000_000.0)
+}
+
+#[derive(Debug, Serialize)]
+pub struct DayBench {
+    pub day: u8,
+    pub runs: u32,
+    pub parse: Stats,
+    pub part1: Stats,
+    // None for days which don't solve part 2 yet
+    pub part2: Option<Stats>,
+}
+
+// Parse and solve a day runs times over, timing each stage separately.
+pub fn bench_day(day: &Day, input: &str, sample: bool, runs: u32) -> DayBench {
+    let mut parse_times = Vec::with_capacity(runs as usize);
+    let mut part1_times = Vec::with_capacity(runs as usize);
+    let mut part2_times = Vec::with_capacity(runs as usize);
+
+    for _ in 0..runs {
+        let (solution, parse_time) = time(|| day.solution(input, sample));
+        parse_times.push(parse_time);
+        part1_times.push(time(|| black_box(solution.part1())).1);
+        let (part2, part2_time) = time(|| black_box(solution.part2()));
+        if part2.is_some() {
+            part2_times.push(part2_time);
+        }
+    }
+
+    DayBench {
+        day: day.number,
+        runs,
+        parse: Stats::from_samples(&parse_times).unwrap(),
+        part1: Stats::from_samples(&part1_times).unwrap(),
+        part2: Stats::from_samples(&part2_times),
+    }
+}
+
+// Lay out bench results with one row per day, and min/median/max for each stage.
+pub fn table(benches: &[DayBench]) -> String {
+    const STAGE_WIDTH: usize = 30;
+
+    let mut table = String::new();
+    writeln!(
+        table,
+        "{:>3}  {:>w$}  {:>w$}  {:>w$}",
+        "Day",
+        "parse",
+        "part1",
+        "part2",
+        w = STAGE_WIDTH
+    )
+    .unwrap();
+    for bench in benches {
+        let part2 = bench
+            .part2
+            .map(format_stats)
+            .unwrap_or_else(|| "-".to_owned());
+        writeln!(
+            table,
+            "{:>3}  {:>w$}  {:>w$}  {:>w$}",
+            bench.day,
+            format_stats(bench.parse),
+            format_stats(bench.part1),
+            part2,
+            w = STAGE_WIDTH
+        )
+        .unwrap();
+    }
+    table
+}
+
+fn format_stats(stats: Stats) -> String {
+    format!(
+        "{} / {} / {}",
+        format_duration(stats.min),
+        format_duration(stats.median),
+        format_duration(stats.max)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_odd_samples() {
+        let samples = [5, 1, 3].map(Duration::from_millis);
+        assert_eq!(
+            Some(Stats {
+                min: Duration::from_millis(1),
+                median: Duration::from_millis(3),
+                max: Duration::from_millis(5),
+            }),
+            Stats::from_samples(&samples)
+        );
+    }
+
+    #[test]
+    fn stats_even_samples() {
+        let samples = [4, 1, 2, 8].map(Duration::from_millis);
+        let stats = Stats::from_samples(&samples).unwrap();
+        assert_eq!(Duration::from_millis(3), stats.median);
+    }
+
+    #[test]
+    fn stats_no_samples() {
+        assert_eq!(None, Stats::from_samples(&[]));
+    }
+
+    #[test]
+    fn json_in_micros() {
+        let stats = Stats::from_samples(&[Duration::from_micros(1500)]).unwrap();
+        assert_eq!(
+            r#"{"min_us":1500.0,"median_us":1500.0,"max_us":1500.0}"#,
+            serde_json::to_string(&stats).unwrap()
+        );
+    }
+}
@@ -0,0 +1,225 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+use crate::graph::{FiniteGraph, Graph};
+
+// Breadth-first searches, for graphs where every edge counts as one step. Edge costs given by the
+// graph are ignored.
+
+// Number of steps on the shortest route from start to end.
+pub fn distance<G: Graph>(graph: &G, start: G::Node, end: G::Node) -> Option<usize> {
+    Search::new(start).solve(graph, &end)
+}
+
+// Shortest route from start to end, start first.
+pub fn path<G: Graph>(graph: &G, start: G::Node, end: G::Node) -> Option<Vec<G::Node>> {
+    let mut search = Search::new(start);
+    search.solve(graph, &end)?;
+    search.path_to(&end)
+}
+
+// Number of steps from start to every node reachable from it.
+pub fn distances_from<G: Graph>(graph: &G, start: G::Node) -> HashMap<G::Node, usize> {
+    let mut search = Search::new(start);
+    search.explore_all(graph);
+    search.into_distances()
+}
+
+// Groups of nodes which can reach each other. Assumes every edge has a matching edge going back
+// the other way.
+pub fn components<G: FiniteGraph>(graph: &G) -> Vec<HashSet<G::Node>> {
+    let mut seen = HashSet::new();
+    let mut components = vec![];
+    for node in graph.nodes() {
+        if seen.contains(&node) {
+            continue;
+        }
+        let mut search = Search::new(node);
+        search.explore_all(graph);
+        let component: HashSet<_> = search.into_distances().into_keys().collect();
+        seen.extend(component.iter().cloned());
+        components.push(component);
+    }
+    components
+}
+
+// Every node reachable from start, as long as none of them fall outside the area given by within.
+// Returns None as soon as the fill escapes.
+pub fn flood_fill<G, P>(graph: &G, start: G::Node, within: P) -> Option<HashSet<G::Node>>
+where
+    G: Graph,
+    P: Fn(&G::Node) -> bool,
+{
+    if !within(&start) {
+        return None;
+    }
+
+    let mut filled = HashSet::from([start.clone()]);
+    let mut to_explore = VecDeque::from([start]);
+    while let Some(node) = to_explore.pop_front() {
+        for (next, _) in graph.neighbours(&node) {
+            if !within(&next) {
+                return None;
+            }
+            if filled.insert(next.clone()) {
+                to_explore.push_back(next);
+            }
+        }
+    }
+    Some(filled)
+}
+
+// Like dijkstra::Solver, records the distance to and parent of each node reached, so routes can be
+// rebuilt afterwards.
+pub struct Search<T: Clone + Eq + Hash> {
+    distances: HashMap<T, usize>,
+    parents: HashMap<T, T>,
+    to_explore: VecDeque<T>,
+}
+
+impl<T: Clone + Eq + Hash> Search<T> {
+    pub fn new(start: T) -> Self {
+        Self::from_starts([start])
+    }
+
+    // Search outwards from several nodes at once, each at distance 0.
+    pub fn from_starts(starts: impl IntoIterator<Item = T>) -> Self {
+        let mut search = Search {
+            distances: HashMap::new(),
+            parents: HashMap::new(),
+            to_explore: VecDeque::new(),
+        };
+        for start in starts {
+            if search.distances.insert(start.clone(), 0).is_none() {
+                search.to_explore.push_back(start);
+            }
+        }
+        search
+    }
+
+    // Explore until end is reached, returning its distance from the nearest start.
+    pub fn solve<G: Graph<Node = T>>(&mut self, graph: &G, end: &T) -> Option<usize> {
+        self.explore(graph, Some(end))
+    }
+
+    // Explore every node reachable from the start(s).
+    pub fn explore_all<G: Graph<Node = T>>(&mut self, graph: &G) {
+        self.explore(graph, None);
+    }
+
+    fn explore<G: Graph<Node = T>>(&mut self, graph: &G, end: Option<&T>) -> Option<usize> {
+        while let Some(node) = self.to_explore.pop_front() {
+            let distance = self.distances[&node];
+            if Some(&node) == end {
+                // Leave it queued, in case the search is continued later
+                self.to_explore.push_front(node);
+                return Some(distance);
+            }
+
+            for (next, _) in graph.neighbours(&node) {
+                if !self.distances.contains_key(&next) {
+                    self.distances.insert(next.clone(), distance + 1);
+                    self.parents.insert(next.clone(), node.clone());
+                    self.to_explore.push_back(next);
+                }
+            }
+        }
+
+        // Did not find a route
+        None
+    }
+
+    pub fn distance(&self, node: &T) -> Option<usize> {
+        self.distances.get(node).copied()
+    }
+
+    pub fn parent(&self, node: &T) -> Option<&T> {
+        self.parents.get(node)
+    }
+
+    pub fn into_distances(self) -> HashMap<T, usize> {
+        self.distances
+    }
+
+    // Follow the parent links back from node to build the route to it, start first.
+    pub fn path_to(&self, node: &T) -> Option<Vec<T>> {
+        self.distances.get(node)?;
+        let mut path = vec![node.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{self, UniformEdges};
+
+    #[test]
+    fn distances_and_paths() {
+        let edges = HashMap::from([
+            (1, vec![2, 3]),
+            (2, vec![4]),
+            (3, vec![4]),
+            (4, vec![5]),
+            (5, vec![]),
+        ]);
+        let graph = UniformEdges(&edges);
+        assert_eq!(Some(3), distance(&graph, 1, 5));
+        assert_eq!(None, distance(&graph, 5, 1));
+        assert_eq!(Some(vec![1, 2, 4, 5]), path(&graph, 1, 5));
+        assert_eq!(
+            HashMap::from([(3, 0), (4, 1), (5, 2)]),
+            distances_from(&graph, 3)
+        );
+
+        let mut search = Search::from_starts([2, 3]);
+        assert_eq!(Some(2), search.solve(&graph, &5));
+        assert_eq!(Some(&2), search.parent(&4));
+    }
+
+    #[test]
+    fn connected_components() {
+        let edges = HashMap::from([
+            (1, vec![2]),
+            (2, vec![1]),
+            (3, vec![4]),
+            (4, vec![3]),
+            (5, vec![]),
+        ]);
+        let mut found: Vec<Vec<_>> = components(&UniformEdges(&edges))
+            .into_iter()
+            .map(|component| {
+                let mut nodes: Vec<_> = component.into_iter().collect();
+                nodes.sort_unstable();
+                nodes
+            })
+            .collect();
+        found.sort_unstable();
+        assert_eq!(vec![vec![1, 2], vec![3, 4], vec![5]], found);
+    }
+
+    #[test]
+    fn bounded_flood_fill() {
+        // A ring of walls around (0, 0) on an unbounded plane
+        let walls: HashSet<(i32, i32)> = [(-1, 0), (1, 0), (0, -1), (0, 1)].into_iter().collect();
+        let open = graph::from_fn(|&(x, y): &(i32, i32)| {
+            [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+                .into_iter()
+                .filter(|next| !walls.contains(next))
+                .map(|next| (next, 1))
+        });
+        let in_box = |&(x, y): &(i32, i32)| x.abs() <= 2 && y.abs() <= 2;
+
+        assert_eq!(
+            Some(HashSet::from([(0, 0)])),
+            flood_fill(&open, (0, 0), in_box)
+        );
+        assert_eq!(None, flood_fill(&open, (2, 2), in_box));
+    }
+}
@@ -1,15 +1,129 @@
-use crate::logger::Logger;
-use clap::Parser;
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::{days::Day, logger::Logger};
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use log::{error, info, warn};
 
 #[derive(Debug, Parser)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
     /// Repeat up to twice for more output.
-    #[arg(short, long, action = clap::ArgAction::Count)]
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
     verbose: u8,
-    /// Use sample input rather than main input.
-    #[arg(short, long)]
+    /// Use sample input rather than main input. With --input, only picks the puzzle parameters
+    /// that go with the sample, e.g. day 15's row.
+    #[arg(short, long, global = true)]
     pub sample: bool,
+    /// Read input from this file, or stdin if "-", instead of the day's own input.
+    #[arg(short, long, global = true)]
+    pub input: Option<PathBuf>,
+    /// Compare answers against those recorded in the day's answers.toml.
+    #[arg(short, long, global = true)]
+    pub check: bool,
+    /// Report how long parsing and each part took.
+    #[arg(short, long, global = true)]
+    pub time: bool,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Solve a single day, or every day if none is given.
+    Run {
+        day: Option<u8>,
+        /// Only solve one part of the puzzle.
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+    },
+    /// Time a single day, or every day if none is given, over repeated runs.
+    Bench {
+        day: Option<u8>,
+        /// How many times to parse and solve each day.
+        #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+        runs: u32,
+        /// Print results as JSON rather than a table.
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+impl Cli {
+    pub fn read_input(&self, day: &Day) -> Result<String> {
+        match &self.input {
+            Some(path) if path == Path::new("-") => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .context("Failed to read input from stdin")?;
+                Ok(input)
+            }
+            Some(path) => read_file(path),
+            None => read_file(&day.input_path(self.sample))
+                .context("Run from the repository root, or set AOC_DAYS_DIR to the days directory"),
+        }
+    }
+}
+
+// Tallies answers compared against the recorded ones in --check mode.
+#[derive(Debug, Default)]
+pub struct CheckSummary {
+    passed: usize,
+    failed: usize,
+    unchecked: usize,
+}
+
+impl CheckSummary {
+    pub fn check(&mut self, day: u8, part: u8, answer: Option<&str>, expected: Option<&str>) {
+        match (answer, expected) {
+            (Some(answer), Some(expected)) if answer == expected => {
+                self.passed += 1;
+                info!("Day {} part {}: {}", day, part, "pass".green());
+            }
+            (Some(answer), Some(expected)) => {
+                self.failed += 1;
+                error!(
+                    "Day {} part {}: fail, expected {} but got {}",
+                    day, part, expected, answer
+                );
+            }
+            (None, Some(_)) => {
+                self.failed += 1;
+                error!("Day {} part {}: fail, not solved", day, part);
+            }
+            (Some(_), None) => {
+                self.unchecked += 1;
+                warn!("Day {} part {}: no recorded answer", day, part);
+            }
+            (None, None) => (),
+        }
+    }
+
+    pub fn report(&self) {
+        let summary = format!(
+            "{} passed, {} failed, {} unchecked",
+            self.passed, self.failed, self.unchecked
+        );
+        if self.failed > 0 {
+            error!("{}", summary);
+        } else {
+            info!("{}", summary.green());
+        }
+    }
+
+    pub fn all_passed(&self) -> bool {
+        self.failed == 0
+    }
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
 }
 
 pub fn startup() -> Cli {
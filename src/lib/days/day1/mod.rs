@@ -0,0 +1,52 @@
+use std::cmp::Reverse;
+
+use crate::days::Solution;
+
+type Int = u32;
+
+pub struct Puzzle {
+    inventories: Vec<Vec<Int>>,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        let inventories = input
+            .split("\n\n")
+            .map(|inventory_str| {
+                inventory_str
+                    .lines()
+                    .map(|calories_str| calories_str.parse::<Int>().unwrap())
+                    .collect()
+            })
+            .collect();
+
+        Puzzle { inventories }
+    }
+
+    fn part1(&self) -> String {
+        part1(&self.inventories).to_string()
+    }
+
+    fn part2(&self) -> Option<String> {
+        Some(part2(&self.inventories).to_string())
+    }
+}
+
+fn part1(inventories: &[Vec<Int>]) -> Int {
+    inventories
+        .iter()
+        .map(|inventory| inventory.iter().sum())
+        .max()
+        .unwrap()
+}
+
+fn part2(inventories: &[Vec<Int>]) -> Int {
+    let mut inventory_sums: Vec<Int> = inventories
+        .iter()
+        .map(|inventory| inventory.iter().sum())
+        .collect();
+
+    inventory_sums.sort_unstable_by_key(|&inventory_sum| Reverse(inventory_sum));
+
+    inventory_sums.iter().take(3).sum()
+}
@@ -1,13 +1,23 @@
-#![feature(iter_intersperse)]
+use crate::days::Solution;
 
-fn main() {
-    let instructions: Vec<_> = include_str!("input.txt")
-        .lines()
-        .map(|line| Instruction::from_str(line))
-        .collect();
+pub struct Puzzle {
+    instructions: Vec<Instruction>,
+}
 
-    println!("Part1: {}", part1(&instructions));
-    part2(&instructions);
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        Puzzle {
+            instructions: input.lines().map(Instruction::from_str).collect(),
+        }
+    }
+
+    fn part1(&self) -> String {
+        part1(&self.instructions).to_string()
+    }
+
+    fn part2(&self) -> Option<String> {
+        Some(part2(&self.instructions))
+    }
 }
 
 fn part1(instructions: &[Instruction]) -> i64 {
@@ -24,7 +34,7 @@ fn part1(instructions: &[Instruction]) -> i64 {
     output
 }
 
-fn part2(instructions: &[Instruction]) {
+fn part2(instructions: &[Instruction]) -> String {
     let mut output = [['.'; 40]; 6];
     let mut video = VideoSystem::new();
 
@@ -39,14 +49,11 @@ fn part2(instructions: &[Instruction]) {
         }
     }
 
-    println!(
-        "{}",
-        output
-            .into_iter()
-            .map(|line| line.into_iter().collect::<String>())
-            .intersperse("\n".to_string())
-            .collect::<String>()
-    );
+    output
+        .into_iter()
+        .map(|line| line.into_iter().collect::<String>())
+        .intersperse("\n".to_string())
+        .collect()
 }
 
 struct VideoSystem {
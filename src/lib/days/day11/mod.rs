@@ -1,20 +1,32 @@
-use std::{cmp::Reverse, collections::VecDeque, ops};
+use std::{cmp::Reverse, collections::VecDeque};
 
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-fn main() {
-    let monkeys_str = include_str!("input.txt");
+use crate::{days::Solution, expr::Expr};
 
-    println!("Part1: {}", part1(monkeys_str));
-    println!("Part2: {}", part2(monkeys_str));
+pub struct Puzzle {
+    monkeys_str: String,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        Puzzle {
+            monkeys_str: input.to_owned(),
+        }
+    }
+
+    fn part1(&self) -> String {
+        part1(&self.monkeys_str).to_string()
+    }
+
+    fn part2(&self) -> Option<String> {
+        Some(part2(&self.monkeys_str).to_string())
+    }
 }
 
 fn part1(monkeys_str: &str) -> u64 {
-    let mut monkeys: Vec<_> = monkeys_str
-        .split("\n\n")
-        .map(|monkey_str| Monkey::from_str(monkey_str))
-        .collect();
+    let mut monkeys: Vec<_> = monkeys_str.split("\n\n").map(Monkey::from_str).collect();
 
     for _round in 0..20 {
         for monkey_id in 0..monkeys.len() {
@@ -28,10 +40,7 @@ fn part1(monkeys_str: &str) -> u64 {
 }
 
 fn part2(monkeys_str: &str) -> u64 {
-    let mut monkeys: Vec<_> = monkeys_str
-        .split("\n\n")
-        .map(|monkey_str| Monkey::from_str(monkey_str))
-        .collect();
+    let mut monkeys: Vec<_> = monkeys_str.split("\n\n").map(Monkey::from_str).collect();
 
     let big_mod = monkeys
         .iter()
@@ -82,7 +91,7 @@ impl Monkey {
             .map(|item| {
                 self.total_inspections += 1;
                 let worry = worry_decreaser((self.operation)(item));
-                let new_monkey = if worry % self.test_number == 0 {
+                let new_monkey = if worry.is_multiple_of(self.test_number) {
                     self.true_monkey
                 } else {
                     self.false_monkey
@@ -107,20 +116,26 @@ impl Monkey {
 
     fn parse_operation(op_line: &str) -> Box<dyn Fn(u64) -> u64> {
         let captures = OP_REGEX.captures(op_line).unwrap();
-        let op = match captures.name("op").unwrap().as_str() {
-            "*" => ops::Mul::mul,
-            "+" => ops::Add::add,
-            not_operator => panic!("Unexpected operator {}", not_operator),
-        };
-
-        let arg_str = captures.name("arg").unwrap().as_str();
-        let arg: Result<u64, _> = arg_str.parse();
-
-        if let Ok(num) = arg {
-            Box::new(move |old| op(old, num))
-        } else {
-            assert!(arg_str == "old");
-            Box::new(move |old| op(old, old))
+        let expr = captures.name("expr").unwrap().as_str().parse().unwrap();
+        Self::compile(&expr)
+    }
+
+    // Worries are always whole, so skip the exact fractions Expr evaluates with, which are far
+    // slower here.
+    fn compile(expr: &Expr) -> Box<dyn Fn(u64) -> u64> {
+        match expr {
+            Expr::Const(value) => {
+                let value: u64 = value.to_integer().try_into().unwrap();
+                Box::new(move |_old| value)
+            }
+            Expr::Var(name) => {
+                assert!(name == "old");
+                Box::new(|old| old)
+            }
+            Expr::Op(op, left, right) => {
+                let (op, left, right) = (*op, Self::compile(left), Self::compile(right));
+                Box::new(move |old| op.checked_call(left(old), right(old)).unwrap())
+            }
         }
     }
 
@@ -151,7 +166,7 @@ static MONKEY_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^Monkey \d:$").unwr
 static ITEMS_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^  Starting items: (?P<nums>[\d, ]*)$").unwrap());
 static OP_REGEX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^  Operation: new = old (?P<op>[*+]) (?P<arg>.*)$").unwrap());
+    Lazy::new(|| Regex::new(r"^  Operation: new = (?P<expr>.*)$").unwrap());
 static TEST_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^  Test: divisible by (?P<num>\d*)").unwrap());
 static HANDOVER_REGEX: Lazy<Regex> =
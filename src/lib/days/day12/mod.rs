@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+use log::{debug, Level::Debug};
+
+use crate::astar;
+use crate::days::Solution;
+use crate::dijkstra;
+use crate::floydwarshall;
+use crate::graph::{self, Graph};
+use crate::grid::{Grid, Pos};
+
+pub struct Puzzle {
+    heights: Grid<u8>,
+    start: Pos,
+    end: Pos,
+    all_starts: Vec<Pos>,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        let mut heights = Grid::parse(input, |c| c as u8).unwrap();
+        let start = heights.find_all(|cell| *cell == b'S').next().unwrap();
+        let end = heights.find_all(|cell| *cell == b'E').next().unwrap();
+        heights[start] = b'a';
+        heights[end] = b'z';
+
+        let all_starts = heights.find_all(|cell| *cell == b'a').collect();
+
+        Puzzle {
+            heights,
+            start,
+            end,
+            all_starts,
+        }
+    }
+
+    fn part1(&self) -> String {
+        part1(&self.heights, self.start, self.end).to_string()
+    }
+
+    fn part2(&self) -> Option<String> {
+        if log::log_enabled!(Debug) {
+            debug!(
+                "Part2 floyd-warshall: {}",
+                part2_fw(&build_map(&self.heights), &self.all_starts, self.end)
+            );
+        }
+        Some(part2(&self.heights, &self.all_starts, self.end).to_string())
+    }
+}
+
+fn part1(heights: &Grid<u8>, start: Pos, end: Pos) -> u64 {
+    astar::distance(&climbable(heights), start, end, astar::manhattan(end)).unwrap()
+}
+
+fn part2(heights: &Grid<u8>, starts: &[Pos], end: Pos) -> u64 {
+    let (_, result) =
+        dijkstra::solve_from_many(&climbable(heights), starts.iter().copied(), end).unwrap();
+    result
+}
+
+fn part2_fw(map: &HashMap<Pos, Vec<Pos>>, starts: &[Pos], end: Pos) -> u64 {
+    let routes: Vec<_> = starts.iter().map(|start| (*start, end)).collect();
+
+    let all_distances = floydwarshall::solve_uniform_edges(map, &routes).unwrap();
+    all_distances.into_iter().flatten().min().unwrap()
+}
+
+// Steps can go down any distance, but only up by at most 1
+fn climbable(heights: &Grid<u8>) -> impl Graph<Node = Pos, Cost = u64> + '_ {
+    graph::from_fn(move |pos: &Pos| {
+        let current_height = heights[*pos];
+        heights
+            .neighbours4(*pos)
+            .filter(move |neighbour| current_height >= heights[*neighbour] - 1)
+            .map(|neighbour| (neighbour, 1))
+    })
+}
+
+fn build_map(heights: &Grid<u8>) -> HashMap<Pos, Vec<Pos>> {
+    let graph = climbable(heights);
+    heights
+        .positions()
+        .map(|pos| (pos, graph.neighbours(&pos).map(|(next, _)| next).collect()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, num::NonZeroUsize, thread};
+
+    use super::*;
+    use crate::days;
+    use crate::graph::UniformEdges;
+    use test::bench::black_box;
+    use test::Bencher;
+
+    #[test]
+    fn test_build_map() {
+        let grid = Grid::from_rows(vec![vec![1, 2], vec![4, 3], vec![5, 6]]).unwrap();
+        let expected_result: HashMap<Pos, Vec<Pos>> = vec![
+            ((0, 0), vec![(0, 1)]),
+            ((0, 1), vec![(1, 1), (0, 0)]),
+            ((1, 0), vec![(0, 0), (2, 0), (1, 1)]),
+            ((1, 1), vec![(0, 1), (1, 0)]),
+            ((2, 0), vec![(1, 0), (2, 1)]),
+            ((2, 1), vec![(1, 1), (2, 0)]),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(expected_result, build_map(&grid));
+    }
+
+    fn input() -> String {
+        fs::read_to_string(days::get(12).unwrap().input_path(false)).unwrap()
+    }
+
+    #[bench]
+    fn astar_single_start(b: &mut Bencher) {
+        let puzzle = Puzzle::parse(&input(), false);
+
+        b.iter(|| black_box(part1(&puzzle.heights, puzzle.start, puzzle.end)))
+    }
+
+    #[bench]
+    fn dijkstra_every_start(b: &mut Bencher) {
+        let puzzle = Puzzle::parse(&input(), false);
+
+        b.iter(|| black_box(part2(&puzzle.heights, &puzzle.all_starts, puzzle.end)))
+    }
+
+    // The whole input has thousands of cells, which takes far too long to solve all pairs for on
+    // every iteration, so just use the first few rows.
+    fn floyd_warshall_map() -> HashMap<Pos, Vec<Pos>> {
+        let rows: String = input()
+            .lines()
+            .take(8)
+            .map(|line| format!("{}\n", line))
+            .collect();
+        build_map(&Grid::parse(&rows, |c| c as u8).unwrap())
+    }
+
+    #[bench]
+    fn floyd_warshall(b: &mut Bencher) {
+        let map = floyd_warshall_map();
+
+        b.iter(|| black_box(floydwarshall::distances_uniform_edges::<_, u64>(&map).unwrap()))
+    }
+
+    #[bench]
+    fn floyd_warshall_one_thread(b: &mut Bencher) {
+        let map = floyd_warshall_map();
+
+        b.iter(|| {
+            black_box(
+                floydwarshall::Solver::from_graph(&UniformEdges(&map))
+                    .unwrap()
+                    .with_threads(1)
+                    .solve()
+                    .unwrap(),
+            )
+        })
+    }
+
+    // part2_fw over the whole map, split between the given number of threads. This takes seconds
+    // per solve, so the benches using it are ignored unless asked for with
+    // `cargo bench -- --ignored full_map`.
+    fn part2_fw_threads(puzzle: &Puzzle, map: &HashMap<Pos, Vec<Pos>>, threads: usize) -> u64 {
+        let distances = floydwarshall::Solver::from_graph(&UniformEdges(map))
+            .unwrap()
+            .with_threads(threads)
+            .solve()
+            .unwrap();
+        puzzle
+            .all_starts
+            .iter()
+            .filter_map(|start| distances.get(start, &puzzle.end))
+            .min()
+            .unwrap()
+    }
+
+    #[bench]
+    #[ignore]
+    fn floyd_warshall_full_map(b: &mut Bencher) {
+        let puzzle = Puzzle::parse(&input(), false);
+        let map = build_map(&puzzle.heights);
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+
+        b.iter(|| black_box(part2_fw_threads(&puzzle, &map, threads)))
+    }
+
+    #[bench]
+    #[ignore]
+    fn floyd_warshall_full_map_one_thread(b: &mut Bencher) {
+        let puzzle = Puzzle::parse(&input(), false);
+        let map = build_map(&puzzle.heights);
+
+        b.iter(|| black_box(part2_fw_threads(&puzzle, &map, 1)))
+    }
+}
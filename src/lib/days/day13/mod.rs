@@ -1,13 +1,27 @@
 use std::cmp::Ordering;
 
-use crate::TreeNode::{Branch, Leaf};
+use crate::days::Solution;
 use itertools::Itertools;
+use TreeNode::{Branch, Leaf};
 
-fn main() {
-    let packets_str = include_str!("input.txt");
+pub struct Puzzle {
+    packets_str: String,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        Puzzle {
+            packets_str: input.to_owned(),
+        }
+    }
 
-    println!("Part1: {}", part1(packets_str));
-    println!("Part2: {}", part2(packets_str))
+    fn part1(&self) -> String {
+        part1(&self.packets_str).to_string()
+    }
+
+    fn part2(&self) -> Option<String> {
+        Some(part2(&self.packets_str).to_string())
+    }
 }
 
 fn part1(packets_str: &str) -> usize {
@@ -33,7 +47,7 @@ fn part2(packets_str: &str) -> usize {
     let mut all_packets: Vec<TreeNode> = packets_str
         .lines()
         .filter_map(|line| {
-            if line.len() > 0 {
+            if !line.is_empty() {
                 Some(TreeNode::from_str(&mut line.chars()).0)
             } else {
                 None
@@ -137,12 +151,12 @@ impl PartialOrd for TreeNode {
 impl Ord for TreeNode {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         match (self, other) {
-            (Leaf(lval), Leaf(rval)) => lval.cmp(&rval),
+            (Leaf(lval), Leaf(rval)) => lval.cmp(rval),
             (Branch(_children), Leaf(val)) => self.cmp(&Branch(vec![Leaf(*val)])),
             (Leaf(val), Branch(_children)) => Branch(vec![Leaf(*val)]).cmp(other),
             (Branch(lchildren), Branch(rchildren)) => {
-                for (lchild, rchild) in lchildren.into_iter().zip(rchildren) {
-                    match lchild.cmp(&rchild) {
+                for (lchild, rchild) in lchildren.iter().zip(rchildren) {
+                    match lchild.cmp(rchild) {
                         Ordering::Equal => (),
                         order => return order,
                     }
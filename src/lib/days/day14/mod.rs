@@ -1,24 +1,35 @@
-#![feature(iter_intersperse)]
-use std::{collections::HashMap, fmt::Debug, thread, time::Duration};
-
+use std::{
+    fmt::{Debug, Display},
+    thread,
+    time::Duration,
+};
+
+use crate::days::Solution;
+use crate::grid::SparseGrid;
 use itertools::Itertools;
-use lib::common_startup;
-use log::{debug, info, trace, Level::*};
+use log::{debug, trace, Level::*};
 use CaveCell::{Air, Rock, Sand, Source};
 
-fn main() {
-    let cli = common_startup::startup();
-    let input = if cli.sample {
-        include_str!("sample_input.txt")
-    } else {
-        include_str!("input.txt")
-    };
-    let cave = Cave::from_input(input);
+pub struct Puzzle {
+    cave: Cave,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        let cave = Cave::from_input(input);
 
-    debug!("{:?}", cave);
+        debug!("{:?}", cave);
+
+        Puzzle { cave }
+    }
+
+    fn part1(&self) -> String {
+        part1(&self.cave).to_string()
+    }
 
-    info!("Part1: {}", part1(&cave));
-    info!("Part1: {}", part2(&cave));
+    fn part2(&self) -> Option<String> {
+        Some(part2(&self.cave).to_string())
+    }
 }
 
 fn part1(cave: &Cave) -> usize {
@@ -53,16 +64,15 @@ fn part2(cave: &Cave) -> usize {
 
 #[derive(Clone)]
 struct Cave {
-    layout: HashMap<(u16, u16), CaveCell>,
-    minx: u16,
-    maxx: u16,
+    // Keyed by (y, x)
+    layout: SparseGrid<CaveCell>,
     maxy: u16,
     floory: u16,
 }
 
 impl Cave {
     fn from_input(rock_structures: &str) -> Self {
-        let mut layout = HashMap::new();
+        let mut layout = SparseGrid::new();
 
         for line in rock_structures.lines() {
             for ((sx, sy), (ex, ey)) in line
@@ -79,10 +89,10 @@ impl Cave {
             {
                 match (sx != ex, sy != ey) {
                     (true, false) => {
-                        layout.extend((sx.min(ex)..=sx.max(ex)).map(|x| ((x, sy), Rock)))
+                        layout.extend((sx.min(ex)..=sx.max(ex)).map(|x| (key(x, sy), Rock)))
                     }
                     (false, true) => {
-                        layout.extend((sy.min(ey)..=sy.max(ey)).map(|y| ((sx, y), Rock)))
+                        layout.extend((sy.min(ey)..=sy.max(ey)).map(|y| (key(sx, y), Rock)))
                     }
                     _ => panic!(
                         "Non horizontal/vertical line found: {:?} -> {:?}",
@@ -93,16 +103,12 @@ impl Cave {
             }
         }
 
-        layout.insert(SOURCE, Source);
+        layout.insert(key(SOURCE.0, SOURCE.1), Source);
 
-        let minx = layout.keys().min_by_key(|(x, _y)| x).unwrap().0;
-        let maxx = layout.keys().max_by_key(|(x, _y)| x).unwrap().0;
-        let maxy = layout.keys().max_by_key(|(_x, y)| y).unwrap().1;
+        let maxy = layout.bounds().unwrap().1 .0 as u16;
 
         Cave {
             layout,
-            minx,
-            maxx,
             maxy,
             floory: maxy + 2,
         }
@@ -111,8 +117,8 @@ impl Cave {
     fn get_cell(&self, x: u16, y: u16) -> CaveCell {
         *self
             .layout
-            .get(&(x, y))
-            .unwrap_or_else(|| if y >= self.floory { &Rock } else { &Air })
+            .get(key(x, y))
+            .unwrap_or(if y >= self.floory { &Rock } else { &Air })
     }
 
     // Returns whether we can drop another grain
@@ -129,9 +135,7 @@ impl Cave {
             return false;
         }
 
-        self.layout.insert((cx, cy), Sand);
-        self.minx = cx.min(self.minx);
-        self.maxx = cx.max(self.maxx);
+        self.layout.insert(key(cx, cy), Sand);
         true
     }
 
@@ -150,30 +154,11 @@ impl Cave {
 
 impl Debug for Cave {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut output =
-            vec![vec![Air; (self.maxx - self.minx + 1) as usize]; (self.floory) as usize];
-
-        for (&(x, y), &cell) in &self.layout {
-            output[y as usize][(x - self.minx) as usize] = cell;
-        }
-
-        write!(
-            f,
-            "\n{}",
-            Iterator::intersperse(
-                output.into_iter().map(|line| {
-                    line.into_iter()
-                        .map(|cell| cell.to_string())
-                        .collect::<String>()
-                }),
-                "\n".to_string(),
-            )
-            .collect::<String>(),
-        )
+        write!(f, "\n{}", self.layout.to_grid(Air).0)
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum CaveCell {
     Air,
     Rock,
@@ -182,21 +167,24 @@ enum CaveCell {
 }
 
 impl CaveCell {
-    fn to_string(&self) -> String {
+    fn as_str(self) -> &'static str {
         match self {
             Air => ".",
             Rock => "#",
             Sand => "o",
             Source => "+",
         }
-        .to_string()
     }
 }
 
-impl Debug for CaveCell {
+impl Display for CaveCell {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.to_string())
+        f.write_str(self.as_str())
     }
 }
 
+fn key(x: u16, y: u16) -> (isize, isize) {
+    (y as isize, x as isize)
+}
+
 const SOURCE: (u16, u16) = (500, 0);
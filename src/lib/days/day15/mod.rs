@@ -0,0 +1,129 @@
+use std::str::FromStr;
+
+use crate::days::Solution;
+use crate::geom::Point2;
+use crate::interval::IntervalSet;
+use log::{debug, trace};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+pub struct Puzzle {
+    sensors: Vec<Sensor>,
+    row: i32,
+    max_index: i32,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str, sample: bool) -> Self {
+        let (row, max_index) = if sample {
+            (10, 20)
+        } else {
+            (2_000_000, 4_000_000)
+        };
+
+        Puzzle {
+            sensors: input.lines().map(Sensor::from_input_line).collect(),
+            row,
+            max_index,
+        }
+    }
+
+    fn part1(&self) -> String {
+        part1(&self.sensors, self.row).to_string()
+    }
+
+    fn part2(&self) -> Option<String> {
+        Some(part2(&self.sensors, self.max_index).to_string())
+    }
+}
+
+fn part1(sensors: &[Sensor], row: i32) -> u64 {
+    let mut blocked_space = blocked_in_row(sensors, row);
+
+    for sensor in sensors {
+        if sensor.nearest_beacon.y == row {
+            blocked_space.remove(sensor.nearest_beacon.x, sensor.nearest_beacon.x);
+        }
+    }
+
+    blocked_space.len()
+}
+
+fn part2(sensors: &[Sensor], max_index: i32) -> i64 {
+    for row in 0..=max_index {
+        if row % 1000 == 0 {
+            debug!("{}", row);
+        }
+        let blocked_space = blocked_in_row(sensors, row);
+        trace!("{:?}", blocked_space);
+        if let Some(x) = blocked_space.first_uncovered(0).filter(|x| *x <= max_index) {
+            debug!("{:?}, {}", blocked_space, x);
+            return x as i64 * 4_000_000 + row as i64;
+        }
+    }
+    panic!()
+}
+
+fn blocked_in_row(sensors: &[Sensor], row: i32) -> IntervalSet<i32> {
+    let mut blocked_space = IntervalSet::new();
+    for sensor in sensors {
+        let (min, max) = sensor.blocked_interval(row);
+        blocked_space.insert(min, max);
+    }
+    blocked_space
+}
+
+struct Sensor {
+    pos: Point2<i32>,
+    nearest_beacon: Point2<i32>,
+    beacon_dist: i32,
+}
+
+impl Sensor {
+    fn from_input_line(line: &str) -> Self {
+        let captures = LINE_REGEX.captures(line).unwrap();
+        let sx: i32 = captures
+            .name("sx")
+            .and_then(|cap| cap.as_str().parse().ok())
+            .unwrap();
+        let sy: i32 = captures
+            .name("sy")
+            .and_then(|cap| cap.as_str().parse().ok())
+            .unwrap();
+        let bx: i32 = captures
+            .name("bx")
+            .and_then(|cap| cap.as_str().parse().ok())
+            .unwrap();
+        let by: i32 = captures
+            .name("by")
+            .and_then(|cap| cap.as_str().parse().ok())
+            .unwrap();
+        let pos = Point2::new(sx, sy);
+        let nearest_beacon = Point2::new(bx, by);
+        Sensor {
+            pos,
+            nearest_beacon,
+            beacon_dist: pos.manhattan(&nearest_beacon),
+        }
+    }
+
+    // One more than how far either side of the sensor's column this row is covered, or 0 if the
+    // row is out of range
+    fn remaining_dist(&self, row: i32) -> i32 {
+        let dist_to_row = (self.pos.y - row).abs();
+        (self.beacon_dist + 1 - dist_to_row).max(0)
+    }
+
+    // Empty (min > max) if the row is out of range
+    fn blocked_interval(&self, row: i32) -> (i32, i32) {
+        let remaining_dist = self.remaining_dist(row);
+        (
+            self.pos.x - remaining_dist + 1,
+            self.pos.x + remaining_dist - 1,
+        )
+    }
+}
+
+static LINE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::from_str(r"Sensor at x=(?P<sx>-?\d*), y=(?P<sy>-?\d*): closest beacon is at x=(?P<bx>-?\d*), y=(?P<by>-?\d*)").unwrap()
+});
@@ -1,65 +1,53 @@
 use std::collections::{HashMap, HashSet};
 
-use lib::{common_startup::startup, floydwarshall};
-use log::{debug, info, log_enabled, trace, Level};
+use crate::{days::Solution, floydwarshall};
+use log::{debug, log_enabled, trace, Level};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-fn main() {
-    let cli = startup();
-    let input = if cli.sample {
-        include_str!("sample_input.txt")
-    } else {
-        include_str!("input.txt")
-    };
-
-    let valves: Vec<Valve> = input
-        .lines()
-        .map(|line| Valve::from_input_line(line))
-        .collect();
-    let id_to_valve: HashMap<String, &Valve> = valves
-        .iter()
-        .map(|valve| (valve.id.clone(), valve))
-        .collect();
+pub struct Puzzle {
+    connections: HashMap<String, HashMap<String, i32>>,
+    id_to_valve: HashMap<String, Valve>,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        let valves: Vec<Valve> = input.lines().map(Valve::from_input_line).collect();
+        let connections = get_route_dists(&valves);
+        let id_to_valve = valves
+            .into_iter()
+            .map(|valve| (valve.id.clone(), valve))
+            .collect();
+
+        Puzzle {
+            connections,
+            id_to_valve,
+        }
+    }
 
-    let connections: HashMap<String, HashMap<String, i32>> = get_route_dists(&valves);
+    fn part1(&self) -> String {
+        part1(&self.connections, &self.id_to_valve).to_string()
+    }
 
-    info!("Part1: {}", part1(&connections, &id_to_valve));
-    info!("Part2: {}", part2(&connections, &id_to_valve));
+    fn part2(&self) -> Option<String> {
+        Some(part2(&self.connections, &self.id_to_valve).to_string())
+    }
 }
 
 fn get_route_dists(valves: &[Valve]) -> HashMap<String, HashMap<String, i32>> {
-    let valves_we_care_about: Vec<&Valve> = valves
-        .iter()
-        .filter(|valve| valve.flow_rate > 0 || valve.id == "AA")
-        .collect();
-    let mut connections_we_care_about = Vec::with_capacity(valves_we_care_about.len().pow(2));
-    for v1 in valves_we_care_about.iter() {
-        for v2 in valves_we_care_about.iter() {
-            if v1 != v2 {
-                connections_we_care_about.push((v1.id.clone(), v2.id.clone()));
-            }
-        }
-    }
     let all_edges: HashMap<String, Vec<String>> = valves
         .iter()
         .map(|valve| (valve.id.clone(), valve.neighbours.clone()))
         .collect();
-    let dists: Vec<Option<i32>> =
-        floydwarshall::solve_uniform_edges(&all_edges, &connections_we_care_about);
-
-    let mut connections: HashMap<String, HashMap<String, i32>> =
-        HashMap::with_capacity(valves_we_care_about.len());
-    for ((start, end), dist) in connections_we_care_about
+    let valves_we_care_about = valves
         .iter()
-        .zip(dists)
-        .map(|(route, dist)| (route, dist.unwrap()))
-    {
-        connections
-            .entry(start.clone())
-            .or_default()
-            .insert(end.clone(), dist);
-    }
+        .filter(|valve| valve.flow_rate > 0 || valve.id == "AA")
+        .map(|valve| valve.id.clone());
+
+    let connections = floydwarshall::distances_uniform_edges(&all_edges)
+        .unwrap()
+        .restrict(valves_we_care_about)
+        .to_map();
 
     debug!("{:?} - {}", connections, connections.len());
 
@@ -68,14 +56,14 @@ fn get_route_dists(valves: &[Valve]) -> HashMap<String, HashMap<String, i32>> {
 
 fn part1(
     connections: &HashMap<String, HashMap<String, i32>>,
-    id_to_valve: &HashMap<String, &Valve>,
+    id_to_valve: &HashMap<String, Valve>,
 ) -> i32 {
     let mut max_pressure_released = 0;
     let mut remaining_paths = vec![Path::new("AA", 30)];
 
     while let Some(current_path) = remaining_paths.pop() {
         for (next_valve, dist) in &connections[&current_path.current_valve] {
-            if let Some(new_path) = current_path.with_extra_step(id_to_valve[next_valve], *dist) {
+            if let Some(new_path) = current_path.with_extra_step(&id_to_valve[next_valve], *dist) {
                 max_pressure_released = new_path.pressure_released.max(max_pressure_released);
                 remaining_paths.push(new_path)
             }
@@ -87,7 +75,7 @@ fn part1(
 
 fn part2(
     connections: &HashMap<String, HashMap<String, i32>>,
-    id_to_valve: &HashMap<String, &Valve>,
+    id_to_valve: &HashMap<String, Valve>,
 ) -> i32 {
     let max_cost = connections
         .values()
@@ -98,7 +86,7 @@ fn part2(
 
     let mut max_pressure_released = 0;
     let mut remaining_paths = vec![(Path::new("AA", 26), Path::new("AA", 26))];
-    let all_valves: HashSet<String> = connections.keys().map(|valve| valve.clone()).collect();
+    let all_valves: HashSet<String> = connections.keys().cloned().collect();
 
     let mut i = 0;
     while let Some((p1, p2)) = remaining_paths.pop() {
@@ -113,19 +101,19 @@ fn part2(
                         let p2v2: i32 = connections[&p2.current_valve][v2];
                         let (new_p1, new_p2) = if p1v1 + p2v2 < p1v2 + p2v1 {
                             (
-                                p1.with_extra_step(id_to_valve[v1], p1v1)
+                                p1.with_extra_step(&id_to_valve[v1], p1v1)
                                     .unwrap()
                                     .with_updated_visited(v2),
-                                p2.with_extra_step(id_to_valve[v2], p2v2)
+                                p2.with_extra_step(&id_to_valve[v2], p2v2)
                                     .unwrap()
                                     .with_updated_visited(v1),
                             )
                         } else {
                             (
-                                p1.with_extra_step(id_to_valve[v2], p1v2)
+                                p1.with_extra_step(&id_to_valve[v2], p1v2)
                                     .unwrap()
                                     .with_updated_visited(v1),
-                                p2.with_extra_step(id_to_valve[v1], p2v1)
+                                p2.with_extra_step(&id_to_valve[v1], p2v1)
                                     .unwrap()
                                     .with_updated_visited(v2),
                             )
@@ -139,7 +127,8 @@ fn part2(
         } else {
             for (moving, waiting) in [(&p1, &p2), (&p2, &p1)] {
                 for (next_valve, dist) in &connections[&moving.current_valve] {
-                    if let Some(new_path) = moving.with_extra_step(id_to_valve[next_valve], *dist) {
+                    if let Some(new_path) = moving.with_extra_step(&id_to_valve[next_valve], *dist)
+                    {
                         max_pressure_released = max_pressure_released
                             .max(new_path.pressure_released + waiting.pressure_released);
                         remaining_paths.push((new_path, waiting.with_updated_visited(next_valve)));
@@ -194,14 +183,14 @@ impl Path {
             visited.insert(next_valve.id.to_owned());
             let pressure_released =
                 self.pressure_released + next_valve.release_pressure(time_remaining);
-            return Some(Path {
+            Some(Path {
                 visited,
                 current_valve: next_valve.id.to_owned(),
                 time_remaining,
                 pressure_released,
-            });
+            })
         } else {
-            return None;
+            None
         }
     }
     fn with_updated_visited(&self, extra_valve: &str) -> Self {
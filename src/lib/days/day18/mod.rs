@@ -0,0 +1,148 @@
+use std::collections::{hash_set, HashSet};
+
+use crate::bfs;
+use crate::days::Solution;
+use crate::geom::Point3;
+use crate::graph;
+use itertools::Itertools;
+use log::{debug, trace};
+
+type Cell = Point3<i8>;
+
+pub struct Puzzle {
+    droplet: Droplet,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        let droplet = input
+            .lines()
+            .map(|line| {
+                line.split(',')
+                    .map(|num| num.parse().unwrap())
+                    .collect_tuple::<(i8, i8, i8)>()
+                    .unwrap()
+                    .into()
+            })
+            .collect();
+
+        Puzzle { droplet }
+    }
+
+    fn part1(&self) -> String {
+        part1(&self.droplet).to_string()
+    }
+
+    fn part2(&self) -> Option<String> {
+        Some(part2(&self.droplet).to_string())
+    }
+}
+
+fn part1(droplet: &Droplet) -> usize {
+    let mut faces = droplet.len() * 6;
+
+    debug!("{:?}", droplet);
+
+    for cell in droplet {
+        for neighbour in cell.neighbours6() {
+            if droplet.contains(&neighbour) {
+                faces -= 1
+            }
+            trace!("{:?}, {:?}, {}", cell, neighbour, faces);
+        }
+    }
+
+    faces
+}
+
+fn part2(droplet: &Droplet) -> usize {
+    let mut droplet_to_fill = droplet.clone();
+    let mut faces = droplet_to_fill.len() * 6;
+
+    debug!("{:?}", droplet_to_fill);
+
+    for cell in droplet {
+        for neighbour in cell.neighbours6() {
+            if neighbour.is_bounded_by(&mut droplet_to_fill) {
+                faces -= 1
+            }
+            trace!("{:?}, {:?}, {}", cell, neighbour, faces);
+        }
+    }
+
+    faces
+}
+
+#[derive(Clone, Debug)]
+struct Droplet {
+    cells: HashSet<Cell>,
+    min: Cell,
+    max: Cell,
+}
+
+impl Droplet {
+    fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn contains(&self, value: &Cell) -> bool {
+        self.cells.contains(value)
+    }
+}
+
+impl FromIterator<Cell> for Droplet {
+    fn from_iter<T: IntoIterator<Item = Cell>>(iter: T) -> Self {
+        let mut min = Point3::new(i8::MAX, i8::MAX, i8::MAX);
+        let mut max = Point3::new(i8::MIN, i8::MIN, i8::MIN);
+        let cells = iter
+            .into_iter()
+            .inspect(|cell| {
+                min = min.component_min(cell);
+                max = max.component_max(cell);
+            })
+            .collect();
+
+        Droplet { cells, min, max }
+    }
+}
+
+impl<'a> IntoIterator for &'a Droplet {
+    type Item = &'a Cell;
+
+    type IntoIter = hash_set::Iter<'a, Cell>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.iter()
+    }
+}
+
+trait CellInDroplet {
+    fn is_bounded_by(&self, droplet: &mut Droplet) -> bool;
+}
+
+impl CellInDroplet for Cell {
+    fn is_bounded_by(&self, droplet: &mut Droplet) -> bool {
+        if droplet.contains(self) {
+            return true;
+        }
+
+        // Flood the air around this cell. If it can get outside the droplet's bounding box, it is
+        // open to the outside; otherwise it is a pocket, which can be filled in.
+        let air = graph::from_fn(|cell: &Cell| {
+            let droplet = &*droplet;
+            cell.neighbours6()
+                .into_iter()
+                .filter(move |neighbour| !droplet.contains(neighbour))
+                .map(|neighbour| (neighbour, 1))
+        });
+        let pocket = bfs::flood_fill(&air, *self, |cell| cell.within(&droplet.min, &droplet.max));
+
+        match pocket {
+            Some(pocket) => {
+                droplet.cells.extend(pocket);
+                true
+            }
+            None => false,
+        }
+    }
+}
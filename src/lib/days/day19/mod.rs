@@ -1,44 +1,46 @@
 use std::{error::Error, fmt::Display, str::FromStr};
 
+use crate::days::Solution;
 use anyhow::Result;
-use lib::common_startup::startup;
-use log::{info, trace, Level};
+use log::{trace, Level};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use BotType::*;
 
-fn main() {
-    let cli = startup();
-    let input = if cli.sample {
-        include_str!("sample_input.txt")
-    } else {
-        include_str!("input.txt")
-    };
+pub struct Puzzle {
+    templates: Vec<Template>,
+}
 
-    let templates: Vec<Template> = input.lines().map(|line| line.parse().unwrap()).collect();
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        Puzzle {
+            templates: input.lines().map(|line| line.parse().unwrap()).collect(),
+        }
+    }
 
-    info!("Part1: {}", part1(&templates));
-    // info!("Part2: {}", part2());
+    fn part1(&self) -> String {
+        part1(&self.templates).to_string()
+    }
 }
 
-fn part1(templates: &Vec<Template>) -> u8 {
+fn part1(templates: &[Template]) -> u32 {
     templates
-        .into_iter()
-        .map(|template| template.get_max_geodes())
-        .max()
-        .unwrap()
+        .iter()
+        .map(|template| template.id as u32 * template.get_max_geodes() as u32)
+        .sum()
 }
 
-fn part2() {}
-
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 struct MiningState {
     time: u8,
-    ore: u8,
-    clay: u8,
-    obsidian: u8,
-    geodes: u8,
-    bots: [u8; BOT_TYPES],
+    ore: u16,
+    clay: u16,
+    obsidian: u16,
+    geodes: u16,
+    bots: [u16; BOT_TYPES],
+    // Bots we could have built but chose to wait instead. Building one of these straight after
+    // waiting is never better than having built it before the wait.
+    skipped: [bool; BOT_TYPES],
 }
 
 impl MiningState {
@@ -50,11 +52,13 @@ impl MiningState {
             obsidian: 0,
             geodes: 0,
             bots: [1, 0, 0, 0],
+            skipped: [false; BOT_TYPES],
         }
     }
 
     fn with_extra_bot(&self, bot_recipe: Recipe) -> Option<MiningState> {
-        if self.ore >= bot_recipe.ore
+        if !self.skipped[bot_recipe.bot_type as usize]
+            && self.ore >= bot_recipe.ore
             && self.clay >= bot_recipe.clay
             && self.obsidian >= bot_recipe.obsidian
         {
@@ -67,20 +71,28 @@ impl MiningState {
                 obsidian: self.obsidian - bot_recipe.obsidian + self.bots[Obsidian as usize],
                 geodes: self.geodes + self.bots[Geode as usize],
                 bots,
+                skipped: [false; BOT_TYPES],
             })
         } else {
             None
         }
     }
 
-    fn with_wait(&self) -> MiningState {
+    // Upper bound assuming we could build a geode bot every remaining minute.
+    fn max_possible_geodes(&self) -> u16 {
+        let time = self.time as u16;
+        self.geodes + self.bots[Geode as usize] * time + time * time.saturating_sub(1) / 2
+    }
+
+    fn with_wait(&self, affordable: [bool; BOT_TYPES]) -> MiningState {
         MiningState {
             time: self.time - 1,
             ore: self.ore + self.bots[Ore as usize],
             clay: self.clay + self.bots[Clay as usize],
-            obsidian: self.obsidian + self.bots[Clay as usize],
+            obsidian: self.obsidian + self.bots[Obsidian as usize],
             geodes: self.geodes + self.bots[Geode as usize],
             bots: self.bots,
+            skipped: affordable,
         }
     }
 }
@@ -95,21 +107,31 @@ struct Template {
 }
 
 impl Template {
-    fn get_max_geodes(&self) -> u8 {
+    fn get_max_geodes(&self) -> u16 {
         let mut max_geodes = 0;
         let mut states = vec![MiningState::new()];
         let mut iters = 0;
         while let Some(current_state) = states.pop() {
-            if current_state.time <= 0 {
+            if current_state.time == 0 {
                 max_geodes = max_geodes.max(current_state.geodes)
-            } else {
-                let mut new_states: Vec<_> = ALL_TYPES
+            } else if current_state.max_possible_geodes() > max_geodes {
+                let useful_types: Vec<_> = ALL_TYPES
+                    .into_iter()
+                    .filter(|bot_type| self.worth_building(*bot_type, &current_state))
+                    .collect();
+                let mut affordable = [false; BOT_TYPES];
+                let mut new_states: Vec<_> = useful_types
                     .iter()
-                    .filter_map(|bot_type| current_state.with_extra_bot(self.recipe(*bot_type)))
+                    .filter_map(|bot_type| {
+                        let new_state = current_state.with_extra_bot(self.recipe(*bot_type));
+                        affordable[*bot_type as usize] = new_state.is_some();
+                        new_state
+                    })
                     .collect();
 
-                if new_states.len() < BOT_TYPES {
-                    new_states.push(current_state.with_wait());
+                // Only worth waiting if that lets us build something we can't build yet
+                if new_states.len() < useful_types.len() {
+                    new_states.insert(0, current_state.with_wait(affordable));
                 }
 
                 states.append(&mut new_states);
@@ -130,6 +152,27 @@ impl Template {
         max_geodes
     }
 
+    // We can only spend so much of each resource per minute, so there's no point having more bots
+    // producing it than that. Geodes are the goal, so more geode bots are always welcome.
+    fn worth_building(&self, bot_type: BotType, state: &MiningState) -> bool {
+        let most_needed = [
+            self.ore_bot,
+            self.clay_bot,
+            self.obsidian_bot,
+            self.geode_bot,
+        ]
+        .iter()
+        .map(|recipe| match bot_type {
+            Ore => recipe.ore,
+            Clay => recipe.clay,
+            Obsidian => recipe.obsidian,
+            Geode => u16::MAX,
+        })
+        .max()
+        .unwrap();
+        state.bots[bot_type as usize] < most_needed
+    }
+
     fn recipe(&self, bot_type: BotType) -> Recipe {
         match bot_type {
             Ore => self.ore_bot,
@@ -150,32 +193,32 @@ impl FromStr for Template {
             .ok_or(TemplateMatchError {})?
             .as_str()
             .parse()?;
-        let oboc: u8 = captures
+        let oboc: u16 = captures
             .name("oboc")
             .ok_or(TemplateMatchError {})?
             .as_str()
             .parse()?;
-        let cboc: u8 = captures
+        let cboc: u16 = captures
             .name("cboc")
             .ok_or(TemplateMatchError {})?
             .as_str()
             .parse()?;
-        let obboc: u8 = captures
+        let obboc: u16 = captures
             .name("obboc")
             .ok_or(TemplateMatchError {})?
             .as_str()
             .parse()?;
-        let obbcc: u8 = captures
+        let obbcc: u16 = captures
             .name("obbcc")
             .ok_or(TemplateMatchError {})?
             .as_str()
             .parse()?;
-        let gboc: u8 = captures
+        let gboc: u16 = captures
             .name("gboc")
             .ok_or(TemplateMatchError {})?
             .as_str()
             .parse()?;
-        let gbobc: u8 = captures
+        let gbobc: u16 = captures
             .name("gbobc")
             .ok_or(TemplateMatchError {})?
             .as_str()
@@ -212,9 +255,9 @@ impl FromStr for Template {
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 struct Recipe {
-    ore: u8,
-    clay: u8,
-    obsidian: u8,
+    ore: u16,
+    clay: u16,
+    obsidian: u16,
     bot_type: BotType,
 }
 
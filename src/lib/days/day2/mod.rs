@@ -0,0 +1,69 @@
+use crate::days::Solution;
+
+type Int = u16;
+
+pub struct Puzzle {
+    strategy: Vec<(String, String)>,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        let strategy = input
+            .lines()
+            .map(|line| {
+                let mut moves = line.split(' ');
+                (
+                    moves.next().unwrap().to_owned(),
+                    moves.next().unwrap().to_owned(),
+                )
+            })
+            .collect();
+
+        Puzzle { strategy }
+    }
+
+    fn part1(&self) -> String {
+        part1(&self.strategy).to_string()
+    }
+
+    fn part2(&self) -> Option<String> {
+        Some(part2(&self.strategy).to_string())
+    }
+}
+
+fn part1(strategy: &[(String, String)]) -> Int {
+    score_strategy(strategy, |round| match round {
+        ("A", "X") => 4,
+        ("A", "Y") => 8,
+        ("A", "Z") => 3,
+        ("B", "X") => 1,
+        ("B", "Y") => 5,
+        ("B", "Z") => 9,
+        ("C", "X") => 7,
+        ("C", "Y") => 2,
+        ("C", "Z") => 6,
+        _ => panic!("Unexpected input"),
+    })
+}
+
+fn part2(strategy: &[(String, String)]) -> Int {
+    score_strategy(strategy, |round| match round {
+        ("A", "X") => 3,
+        ("A", "Y") => 4,
+        ("A", "Z") => 8,
+        ("B", "X") => 1,
+        ("B", "Y") => 5,
+        ("B", "Z") => 9,
+        ("C", "X") => 2,
+        ("C", "Y") => 6,
+        ("C", "Z") => 7,
+        _ => panic!("Unexpected input"),
+    })
+}
+
+fn score_strategy<F: Fn((&str, &str)) -> Int>(strategy: &[(String, String)], scoring: F) -> Int {
+    strategy
+        .iter()
+        .map(|(opponent, response)| scoring((opponent, response)))
+        .sum()
+}
@@ -4,21 +4,27 @@ use std::{
     ops::{Index, IndexMut},
 };
 
-use lib::common_startup::startup;
-use log::{debug, info, trace};
+use crate::days::Solution;
+use log::{debug, trace};
 
-fn main() {
-    let cli = startup();
-    let input = if cli.sample {
-        include_str!("sample_input.txt")
-    } else {
-        include_str!("input.txt")
-    };
+pub struct Puzzle {
+    encrypted: Vec<isize>,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        Puzzle {
+            encrypted: input.lines().map(|line| line.parse().unwrap()).collect(),
+        }
+    }
 
-    let encrypted: Vec<isize> = input.lines().map(|line| line.parse().unwrap()).collect();
+    fn part1(&self) -> String {
+        part1(&self.encrypted).to_string()
+    }
 
-    info!("Part1: {}", part1(&encrypted));
-    info!("Part2: {}", part2(&encrypted));
+    fn part2(&self) -> Option<String> {
+        Some(part2(&self.encrypted).to_string())
+    }
 }
 
 fn part1(encrypted: &[isize]) -> isize {
@@ -26,7 +32,7 @@ fn part1(encrypted: &[isize]) -> isize {
     let file_len = encrypted.len() as isize;
     let mut positions: Vec<isize> = (0..file_len).collect();
     let mut to_mix: RingBuffer<_> = encrypted
-        .into_iter()
+        .iter()
         .enumerate()
         .map(|(i, &val)| {
             if val == 0 {
@@ -51,7 +57,7 @@ fn part2(encrypted: &[isize]) -> isize {
     let file_len = encrypted.len() as isize;
     let mut positions: Vec<isize> = (0..file_len).collect();
     let mut to_mix: RingBuffer<_> = encrypted
-        .into_iter()
+        .iter()
         .enumerate()
         .map(|(i, &val)| {
             if val == 0 {
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use crate::days::Solution;
+use crate::expr::{self, Expr, Rational};
+use log::debug;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+pub struct Puzzle {
+    monkeys: HashMap<String, MonkeyNum>,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        let monkeys = input
+            .lines()
+            .map(|line| {
+                let num = MonkeyNum::from_str(line);
+                (num.id.clone(), num)
+            })
+            .collect();
+
+        Puzzle { monkeys }
+    }
+
+    fn part1(&self) -> String {
+        part1(&self.monkeys).to_string()
+    }
+
+    fn part2(&self) -> Option<String> {
+        Some(part2(&self.monkeys).to_string())
+    }
+}
+
+fn part1(monkeys: &HashMap<String, MonkeyNum>) -> Rational {
+    to_expr("root", monkeys, None)
+        .eval(&HashMap::new())
+        .unwrap()
+}
+
+fn part2(monkeys: &HashMap<String, MonkeyNum>) -> Rational {
+    let Expr::Op(_, left, right) = &monkeys["root"].job else {
+        panic!("root doesn't compare two monkeys: {:?}", monkeys["root"]);
+    };
+    let mut humn_unknown = |id: &str| to_expr(id, monkeys, Some("humn"));
+    let left = left.substitute(&mut humn_unknown);
+    let right = right.substitute(&mut humn_unknown);
+    debug!("{} = {}", left, right);
+    expr::solve(&left, &right, "humn").unwrap()
+}
+
+// The expression a monkey shouts, with the monkey called unknown (if any) left as a variable.
+fn to_expr(monkey_id: &str, monkeys: &HashMap<String, MonkeyNum>, unknown: Option<&str>) -> Expr {
+    if unknown == Some(monkey_id) {
+        return Expr::var(monkey_id);
+    }
+    monkeys[monkey_id]
+        .job
+        .substitute(&mut |id| to_expr(id, monkeys, unknown))
+}
+
+#[derive(Debug)]
+struct MonkeyNum {
+    id: String,
+    // Other monkeys appear as unknowns named by their ids
+    job: Expr,
+}
+
+impl MonkeyNum {
+    fn from_str(line: &str) -> Self {
+        let caps = MONKEY_REGEX.captures(line).unwrap();
+        Self {
+            id: caps.name("id").unwrap().as_str().to_owned(),
+            job: caps.name("job").unwrap().as_str().parse().unwrap(),
+        }
+    }
+}
+
+static MONKEY_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?P<id>[a-z]{4}): (?P<job>.*)").unwrap());
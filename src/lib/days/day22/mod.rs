@@ -1,33 +1,35 @@
-#![feature(iter_intersperse)]
+use std::{fmt::Display, str::FromStr, vec};
 
-use std::{
-    borrow::BorrowMut,
-    fmt::Display,
-    str::{Chars, FromStr},
-};
-
-use lib::common_startup::startup;
-use log::{debug, info, trace};
+use crate::days::Solution;
+use crate::geom::{Direction, Point2};
+use crate::grid::Grid;
+use log::{debug, trace};
 use Cell::*;
 use Direction::*;
 use Instruction::*;
 
-fn main() {
-    let cli = startup();
-    let input = if cli.sample {
-        include_str!("sample_input.txt")
-    } else {
-        include_str!("input.txt")
-    };
+pub struct Puzzle {
+    board: Board,
+    instructions: InstructionIter,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        let (board_str, instructions_str) = input.split_once("\n\n").unwrap();
 
-    let (board_str, instructions_str) = input.split_once("\n\n").unwrap();
+        let board: Board = board_str.parse().unwrap();
+        let instructions = InstructionIter::new(instructions_str);
+        debug!("{}", board);
 
-    let board: Board = board_str.parse().unwrap();
-    let instructions = InstructionIter::new(instructions_str);
-    debug!("{}", board);
+        Puzzle {
+            board,
+            instructions,
+        }
+    }
 
-    info!("Part1: {}", part1(&board, &instructions));
-    // info!("Part2: {}", part2());
+    fn part1(&self) -> String {
+        part1(&self.board, &self.instructions).to_string()
+    }
 }
 
 fn part1(board: &Board, instructions: &InstructionIter) -> isize {
@@ -35,13 +37,12 @@ fn part1(board: &Board, instructions: &InstructionIter) -> isize {
     for instruction in instructions.clone() {
         state.follow_instruction(instruction);
     }
-    (state.pos.0 + 1) * 1000 + (state.pos.1 + 1) * 4 + state.dir as isize
+    (state.pos.y + 1) * 1000 + (state.pos.x + 1) * 4 + state.dir as isize
 }
 
-fn part2() {}
-
 struct PathState {
-    pos: (isize, isize),
+    // x is the column, y the row
+    pos: Point2<isize>,
     dir: Direction,
     board: Board,
 }
@@ -49,9 +50,9 @@ struct PathState {
 impl PathState {
     fn new(board: &Board) -> Self {
         let board = board.clone();
-        let pos = (
+        let pos = Point2::new(
+            board.board.row(0).iter().position(|c| *c == Space).unwrap() as isize,
             0,
-            board.board[0].iter().position(|c| *c == Space).unwrap() as isize,
         );
         let dir = Right;
         PathState { board, pos, dir }
@@ -60,37 +61,27 @@ impl PathState {
     fn follow_instruction(&mut self, instruction: Instruction) {
         match instruction {
             Move(n) => self.pos = self.board.take_steps(self.pos, self.dir, n),
-            TurnLeft => {
-                self.dir = match self.dir {
-                    Up => Left,
-                    Down => Right,
-                    Left => Down,
-                    Right => Up,
-                }
-            }
-            TurnRight => {
-                self.dir = match self.dir {
-                    Up => Right,
-                    Down => Left,
-                    Left => Up,
-                    Right => Down,
-                }
-            }
+            TurnLeft => self.dir = self.dir.turn_left(),
+            TurnRight => self.dir = self.dir.turn_right(),
         }
     }
 }
 
 #[derive(Clone, Debug)]
 struct InstructionIter {
-    instruction_chars: Chars<'static>,
+    instruction_chars: vec::IntoIter<char>,
     last_char: Option<char>,
     flag: bool,
 }
 
 impl InstructionIter {
-    fn new(instruction_str: &'static str) -> Self {
+    fn new(instruction_str: &str) -> Self {
         InstructionIter {
-            instruction_chars: instruction_str.trim().chars(),
+            instruction_chars: instruction_str
+                .trim()
+                .chars()
+                .collect::<Vec<_>>()
+                .into_iter(),
             last_char: None,
             flag: false,
         }
@@ -120,16 +111,16 @@ impl Iterator for InstructionIter {
 
         let mut digits = vec![];
 
-        while let Some(c) = self.instruction_chars.next() {
+        for c in self.instruction_chars.by_ref() {
             trace!("{}", c);
-            if !c.is_digit(10) {
+            if !c.is_ascii_digit() {
                 self.last_char = Some(c);
                 break;
             }
             digits.push(c);
         }
 
-        if digits.len() > 0 {
+        if !digits.is_empty() {
             Some(Move(
                 digits.into_iter().collect::<String>().parse().unwrap(),
             ))
@@ -147,34 +138,13 @@ enum Instruction {
     TurnRight,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Direction {
-    Up = 3,
-    Down = 1,
-    Left = 2,
-    Right = 0,
-}
-
-impl Direction {
-    fn inc(&self) -> (isize, isize) {
-        match self {
-            Up => (-1, 0),
-            Down => (1, 0),
-            Left => (0, -1),
-            Right => (0, 1),
-        }
-    }
-}
-
 #[derive(Clone, Debug)]
 struct Board {
-    board: Vec<Vec<Cell>>,
-    width: usize,
-    height: usize,
+    board: Grid<Cell>,
 }
 
 impl Board {
-    fn take_steps(&self, pos: (isize, isize), dir: Direction, dist: u8) -> (isize, isize) {
+    fn take_steps(&self, pos: Point2<isize>, dir: Direction, dist: u8) -> Point2<isize> {
         let mut new_pos = pos;
         for _ in 0..dist {
             if let Some(step_pos) = self.take_step(new_pos, dir) {
@@ -186,16 +156,12 @@ impl Board {
         new_pos
     }
 
-    fn take_step(&self, pos: (isize, isize), dir: Direction) -> Option<(isize, isize)> {
-        let inc = dir.inc();
-        let mut new_pos = (pos.0 + inc.0, pos.1 + inc.1);
+    fn take_step(&self, pos: Point2<isize>, dir: Direction) -> Option<Point2<isize>> {
+        let mut new_pos = pos.step(dir);
+        let (width, height) = (self.board.width() as isize, self.board.height() as isize);
 
-        if new_pos.0 < self.height as isize
-            && new_pos.0 >= 0
-            && new_pos.1 < self.width as isize
-            && new_pos.1 >= 0
-        {
-            match self.board[new_pos.0 as usize][new_pos.1 as usize] {
+        if new_pos.y < height && new_pos.y >= 0 && new_pos.x < width && new_pos.x >= 0 {
+            match self.cell(new_pos) {
                 Space => {
                     return Some(new_pos);
                 }
@@ -207,21 +173,25 @@ impl Board {
         }
 
         new_pos = match dir {
-            Up => (self.height as isize - 1, new_pos.1),
-            Down => (0, new_pos.1),
-            Left => (new_pos.0, self.width as isize - 1),
-            Right => (new_pos.0, 0),
+            Up => Point2::new(new_pos.x, height - 1),
+            Down => Point2::new(new_pos.x, 0),
+            Left => Point2::new(width - 1, new_pos.y),
+            Right => Point2::new(0, new_pos.y),
         };
 
         loop {
-            match self.board[new_pos.0 as usize][new_pos.1 as usize] {
+            match self.cell(new_pos) {
                 Space => return Some(new_pos),
                 Wall => return None,
                 Buff => (),
             }
-            new_pos = (new_pos.0 + inc.0, new_pos.1 + inc.1);
+            new_pos = new_pos.step(dir);
         }
     }
+
+    fn cell(&self, pos: Point2<isize>) -> Cell {
+        self.board[(pos.y as usize, pos.x as usize)]
+    }
 }
 
 impl Display for Board {
@@ -230,9 +200,9 @@ impl Display for Board {
             f,
             "{}",
             self.board
-                .iter()
+                .rows()
                 .map(|row| row
-                    .into_iter()
+                    .iter()
                     .map(|cell| format!("{}", cell))
                     .collect::<String>()
                     .trim_end()
@@ -247,18 +217,8 @@ impl FromStr for Board {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let width = s.lines().map(|line| line.len()).max().unwrap();
-        let height = s.lines().count();
-        let mut board = vec![vec![Buff; width]; height];
-        for (row, row_str) in s.lines().enumerate() {
-            for (col, cell_char) in row_str.chars().enumerate() {
-                board[row][col] = Cell::new(cell_char);
-            }
-        }
         Ok(Board {
-            board,
-            width,
-            height,
+            board: Grid::parse_padded(s, Buff, Cell::new),
         })
     }
 }
@@ -1,15 +1,30 @@
 use std::collections::HashSet;
 
+use crate::days::Solution;
+
 type Int = u16;
 
-fn main() {
-    let rucksacks: Vec<_> = include_str!("input.txt").lines().collect();
+pub struct Puzzle {
+    rucksacks: Vec<String>,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        Puzzle {
+            rucksacks: input.lines().map(str::to_owned).collect(),
+        }
+    }
+
+    fn part1(&self) -> String {
+        part1(&self.rucksacks).to_string()
+    }
 
-    println!("Part1: {}", part1(&rucksacks));
-    println!("Part2: {}", part2(&rucksacks));
+    fn part2(&self) -> Option<String> {
+        Some(part2(&self.rucksacks).to_string())
+    }
 }
 
-fn part1(rucksacks: &Vec<&str>) -> Int {
+fn part1(rucksacks: &[String]) -> Int {
     let pockets = rucksacks.iter().map(|rucksack| {
         let mut pockets = (
             Vec::with_capacity(rucksack.len() / 2 + 1),
@@ -36,7 +51,7 @@ fn part1(rucksacks: &Vec<&str>) -> Int {
     result
 }
 
-fn part2(rucksacks: &Vec<&str>) -> Int {
+fn part2(rucksacks: &[String]) -> Int {
     let mut result = 0;
 
     for group in rucksacks.chunks(3) {
@@ -0,0 +1,60 @@
+use crate::days::Solution;
+use crate::interval::Interval;
+
+type Pair = (Interval<u8>, Interval<u8>);
+
+pub struct Puzzle {
+    paired_assignments: Vec<Pair>,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        Puzzle {
+            paired_assignments: input.lines().map(assemble_pair).collect(),
+        }
+    }
+
+    fn part1(&self) -> String {
+        part1(&self.paired_assignments).to_string()
+    }
+
+    fn part2(&self) -> Option<String> {
+        Some(part2(&self.paired_assignments).to_string())
+    }
+}
+
+fn part1(paired_assignments: &[Pair]) -> usize {
+    paired_assignments
+        .iter()
+        .filter(|(p0, p1)| p0.contains_all(*p1) || p1.contains_all(*p0))
+        .count()
+}
+
+fn part2(paired_assignments: &[Pair]) -> usize {
+    paired_assignments
+        .iter()
+        .filter(|(p0, p1)| p0.overlaps(*p1))
+        .count()
+}
+
+fn assemble_pair(line: &str) -> Pair {
+    let (p0, p1) = line.split_once(',').unwrap();
+    (p0.parse().unwrap(), p1.parse().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assemble_pairs() {
+        assert_eq!(
+            assemble_pair("2-4,6-8"),
+            (Interval::new(2, 4), Interval::new(6, 8))
+        );
+        assert_eq!(
+            assemble_pair("6-6,4-6"),
+            (Interval::new(6, 6), Interval::new(4, 6))
+        );
+    }
+}
@@ -1,23 +1,45 @@
-fn main() {
-    let mut input_sections = include_str!("input.txt").split("\n\n");
-    let stacks_str = input_sections.next().unwrap();
-    let instructions_str = input_sections.next().unwrap();
-
-    let stacks = parse_stacks(stacks_str);
-    let instructions = instructions_str
-        .lines()
-        .map(|line| Instruction::from_str(line))
-        .collect();
-
-    println!("Part1: {}", part1(&stacks, &instructions));
-    println!("Part2: {}", part2(&stacks, &instructions));
+use crate::days::Solution;
+
+pub struct Puzzle {
+    stacks: Vec<Vec<char>>,
+    instructions: Vec<Instruction>,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        let mut input_sections = input.split("\n\n");
+        let stacks_str = input_sections.next().unwrap();
+        let instructions_str = input_sections.next().unwrap();
+
+        let stacks = parse_stacks(stacks_str);
+        let instructions = instructions_str
+            .lines()
+            .map(Instruction::from_str)
+            .collect();
+
+        Puzzle {
+            stacks,
+            instructions,
+        }
+    }
+
+    fn part1(&self) -> String {
+        part1(&self.stacks, &self.instructions)
+    }
+
+    fn part2(&self) -> Option<String> {
+        Some(part2(&self.stacks, &self.instructions))
+    }
 }
 
 fn parse_stacks(stacks_str: &str) -> Vec<Vec<char>> {
-    let mut stacks: Vec<Vec<char>> = vec![Vec::with_capacity(8); 9];
+    // Work up from the bottom, where the stacks are labelled
+    let mut lines = stacks_str.lines().rev();
+    let stack_count = lines.next().unwrap().split_whitespace().count();
+    let mut stacks: Vec<Vec<char>> = vec![vec![]; stack_count];
 
     // Get useful info from the rows
-    let rows = stacks_str.lines().map(|line| {
+    let rows = lines.map(|line| {
         line.chars()
             .enumerate()
             .filter(|(i, _)| i % 4 == 1)
@@ -26,7 +48,7 @@ fn parse_stacks(stacks_str: &str) -> Vec<Vec<char>> {
     });
 
     // Stick that useful info into the stacks
-    for row in rows.rev() {
+    for row in rows {
         for (i, maybe_c) in row.into_iter().enumerate() {
             if let Some(c) = maybe_c {
                 stacks[i].push(c);
@@ -37,9 +59,9 @@ fn parse_stacks(stacks_str: &str) -> Vec<Vec<char>> {
     stacks
 }
 
-fn part1(stacks: &Vec<Vec<char>>, instructions: &Vec<Instruction>) -> String {
+fn part1(stacks: &[Vec<char>], instructions: &[Instruction]) -> String {
     // Don't modify the input
-    let mut stacks = stacks.clone();
+    let mut stacks = stacks.to_vec();
 
     // Follow the instructions
     for instruction in instructions {
@@ -56,9 +78,9 @@ fn part1(stacks: &Vec<Vec<char>>, instructions: &Vec<Instruction>) -> String {
         .collect()
 }
 
-fn part2(stacks: &Vec<Vec<char>>, instructions: &Vec<Instruction>) -> String {
+fn part2(stacks: &[Vec<char>], instructions: &[Instruction]) -> String {
     // Don't modify the input
-    let mut stacks = stacks.clone();
+    let mut stacks = stacks.to_vec();
 
     // Follow the instrutions
     for instruction in instructions {
@@ -1,22 +1,32 @@
-#![feature(test)]
+use std::collections::{BTreeSet, HashSet};
 
-extern crate test;
+use crate::days::Solution;
+use crate::LetterSet;
 
-use std::collections::{BTreeSet, HashSet};
+pub struct Puzzle {
+    signal: Vec<char>,
+}
 
-use lib::LetterSet;
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        Puzzle {
+            signal: input.chars().collect(),
+        }
+    }
 
-fn main() {
-    let signal: Vec<char> = include_str!("input.txt").chars().collect();
+    fn part1(&self) -> String {
+        search_for_marker(&self.signal, 4, check_for_marker_naive)
+            .unwrap()
+            .to_string()
+    }
 
-    println!(
-        "Part1: {}",
-        search_for_marker(&signal, 4, check_for_marker_naive).unwrap()
-    );
-    println!(
-        "Part2: {}",
-        search_for_marker(&signal, 14, check_for_marker_naive).unwrap()
-    );
+    fn part2(&self) -> Option<String> {
+        Some(
+            search_for_marker(&self.signal, 14, check_for_marker_naive)
+                .unwrap()
+                .to_string(),
+        )
+    }
 }
 
 fn search_for_marker<F>(signal: &[char], marker_size: usize, f: F) -> Option<usize>
@@ -25,7 +35,7 @@ where
 {
     signal
         .windows(marker_size)
-        .position(|packet| f(packet))
+        .position(f)
         .map(|i| i + marker_size)
 }
 
@@ -1,33 +1,40 @@
-#![feature(test)]
+use crate::days::Solution;
+use crate::PositionBinary;
+use std::collections::HashMap;
 
-extern crate test;
+pub struct Puzzle {
+    directory_sizes: HashMap<String, u64>,
+}
 
-use lib::PositionBinary;
-use std::collections::HashMap;
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        let mut dir_walker = DirWalker::default();
+        dir_walker.size_directories(input);
+        Puzzle {
+            directory_sizes: dir_walker.directory_sizes,
+        }
+    }
 
-fn main() {
-    let shell_output = include_str!("input.txt");
+    fn part1(&self) -> String {
+        part1(&self.directory_sizes).to_string()
+    }
 
-    let mut dir_walker = DirWalker::default();
-    let directory_sizes = dir_walker.size_directories(shell_output);
-    println!("Part1: {}", part1(directory_sizes));
-    println!("Part2: {}", part2(directory_sizes));
+    fn part2(&self) -> Option<String> {
+        Some(part2(&self.directory_sizes).to_string())
+    }
 }
 
 fn part1(directory_sizes: &HashMap<String, u64>) -> u64 {
     directory_sizes
-        .iter()
-        .filter_map(|(_, size)| if *size <= 100_000 { Some(size) } else { None })
+        .values()
+        .filter(|size| **size <= 100_000)
         .sum()
 }
 
 fn part2(directory_sizes: &HashMap<String, u64>) -> u64 {
     let min_size = directory_sizes["/"] - 40_000_000;
 
-    let mut sorted_sizes: Vec<_> = directory_sizes
-        .iter()
-        .map(|(_, dir_size)| *dir_size)
-        .collect();
+    let mut sorted_sizes: Vec<_> = directory_sizes.values().copied().collect();
     sorted_sizes.sort_unstable();
 
     sorted_sizes[sorted_sizes
@@ -39,11 +46,11 @@ fn part2(directory_sizes: &HashMap<String, u64>) -> u64 {
 #[derive(Debug, Default)]
 struct DirWalker {
     directory_sizes: HashMap<String, u64>,
-    cwd: Vec<&'static str>,
+    cwd: Vec<String>,
 }
 
 impl DirWalker {
-    fn size_directories<'a>(&'a mut self, shell_output: &'static str) -> &'a HashMap<String, u64> {
+    fn size_directories<'a>(&'a mut self, shell_output: &str) -> &'a HashMap<String, u64> {
         for line in shell_output.lines() {
             let split_line: Vec<_> = line.split_whitespace().collect();
             match (split_line[0], split_line[1]) {
@@ -58,18 +65,18 @@ impl DirWalker {
         &self.directory_sizes
     }
 
-    fn handle_cd(&mut self, dir: &'static str) {
+    fn handle_cd(&mut self, dir: &str) {
         if dir == ".." {
             self.cwd.pop();
         } else {
-            self.cwd.push(dir);
+            self.cwd.push(dir.to_owned());
         }
     }
 
     fn handle_file(&mut self, file_size: u64) {
         let mut dir_to_update = vec![];
         for dir in &self.cwd {
-            dir_to_update.push(dir.to_string());
+            dir_to_update.push(dir.as_str());
             let fq_dir_name = dir_to_update.join("/");
             *self.directory_sizes.entry(fq_dir_name).or_default() += file_size;
         }
@@ -92,10 +99,7 @@ mod tests {
 
         let min_size = directory_sizes["/"] - 40_000_000;
 
-        let mut sorted_sizes: Vec<_> = directory_sizes
-            .iter()
-            .map(|(_, dir_size)| *dir_size)
-            .collect();
+        let mut sorted_sizes: Vec<_> = directory_sizes.values().copied().collect();
         sorted_sizes.sort_unstable();
 
         b.iter(|| black_box(sorted_sizes.iter().position(|size| *size >= min_size)))
@@ -110,10 +114,7 @@ mod tests {
 
         let min_size = directory_sizes["/"] - 40_000_000;
 
-        let mut sorted_sizes: Vec<_> = directory_sizes
-            .iter()
-            .map(|(_, dir_size)| *dir_size)
-            .collect();
+        let mut sorted_sizes: Vec<_> = directory_sizes.values().copied().collect();
         sorted_sizes.sort_unstable();
 
         let sorted_slice = sorted_sizes.as_slice();
@@ -0,0 +1,60 @@
+use crate::{
+    days::Solution,
+    geom::Direction,
+    grid::{self, Grid},
+};
+
+pub struct Puzzle {
+    forest: Grid<u8>,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        let forest = Grid::parse(input, |c| c.to_digit(10).unwrap() as u8).unwrap();
+
+        Puzzle { forest }
+    }
+
+    fn part1(&self) -> String {
+        part1(&self.forest).to_string()
+    }
+
+    fn part2(&self) -> Option<String> {
+        Some(part2(&self.forest).to_string())
+    }
+}
+
+fn part1(forest: &Grid<u8>) -> usize {
+    forest
+        .iter()
+        .filter(|(pos, tree_height)| {
+            Direction::ALL.into_iter().any(|dir| {
+                forest
+                    .ray(*pos, grid::offset(dir))
+                    .all(|other| forest[other] < **tree_height)
+            })
+        })
+        .count()
+}
+
+fn part2(forest: &Grid<u8>) -> usize {
+    forest
+        .iter()
+        .map(|(pos, tree_height)| {
+            Direction::ALL
+                .into_iter()
+                .map(|dir| {
+                    let mut dist = 0;
+                    for other in forest.ray(pos, grid::offset(dir)) {
+                        dist += 1;
+                        if forest[other] >= *tree_height {
+                            break;
+                        }
+                    }
+                    dist
+                })
+                .product()
+        })
+        .max()
+        .unwrap()
+}
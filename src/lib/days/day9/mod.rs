@@ -0,0 +1,103 @@
+use std::{collections::HashSet, thread, time::Duration};
+
+use log::{trace, Level::Trace};
+
+use crate::days::Solution;
+use crate::geom::{Direction, Point2};
+use crate::grid::Grid;
+
+pub struct Puzzle {
+    directions: Vec<(Direction, u8)>,
+}
+
+impl Solution for Puzzle {
+    fn parse(input: &str, _sample: bool) -> Self {
+        let directions = input
+            .lines()
+            .map(|line| {
+                let mut split_line = line.split(' ');
+                (
+                    split_line
+                        .next()
+                        .and_then(|s| s.chars().next())
+                        .and_then(|c| Direction::try_from(c).ok())
+                        .unwrap(),
+                    split_line.next().unwrap().parse().unwrap(),
+                )
+            })
+            .collect();
+
+        Puzzle { directions }
+    }
+
+    fn part1(&self) -> String {
+        part1(&self.directions).to_string()
+    }
+
+    fn part2(&self) -> Option<String> {
+        Some(part2(&self.directions).to_string())
+    }
+}
+
+fn part1(directions: &[(Direction, u8)]) -> usize {
+    let mut h = Point2::new(0, 0);
+    let mut t = Point2::new(0, 0);
+    let mut t_visited: HashSet<Point2<i32>> = HashSet::new();
+
+    for (direction, count) in directions {
+        for _ in 0..*count {
+            h = h.step(*direction);
+            t = move_tail(h, t);
+            t_visited.insert(t);
+        }
+    }
+
+    t_visited.len()
+}
+
+fn part2(directions: &[(Direction, u8)]) -> usize {
+    let mut rope = [Point2::new(0, 0); 10];
+    let mut t_visited: HashSet<Point2<i32>> = HashSet::new();
+
+    for (direction, count) in directions {
+        for _ in 0..*count {
+            rope[0] = rope[0].step(*direction);
+            for knot in 0..(rope.len() - 1) {
+                rope[knot + 1] = move_tail(rope[knot], rope[knot + 1]);
+            }
+            t_visited.insert(rope[9]);
+            if log::log_enabled!(Trace) {
+                visualise_step(&rope, &t_visited);
+            }
+        }
+    }
+
+    t_visited.len()
+}
+
+// The tail only moves once the head is no longer touching it, and then moves one step (possibly
+// diagonally) towards it.
+fn move_tail(h: Point2<i32>, t: Point2<i32>) -> Point2<i32> {
+    if h.chebyshev(&t) > 1 {
+        t + (h - t).signum()
+    } else {
+        t
+    }
+}
+
+fn visualise_step(rope: &[Point2<i32>], past_tails: &HashSet<Point2<i32>>) {
+    let mut grid = Grid::new(GRID_SIZE, GRID_SIZE, '.');
+    for Point2 { x, y } in past_tails {
+        grid[((*y + OFFSET) as usize, (*x + OFFSET) as usize)] = '#';
+    }
+    for (i, Point2 { x, y }) in rope.iter().enumerate().rev() {
+        grid[((*y + OFFSET) as usize, (*x + OFFSET) as usize)] =
+            i.to_string().chars().next().unwrap();
+    }
+    trace!("{}", grid);
+
+    thread::sleep(Duration::from_millis(100));
+}
+
+const GRID_SIZE: usize = 71;
+const OFFSET: i32 = (GRID_SIZE / 2 + 1) as i32;
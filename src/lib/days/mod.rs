@@ -0,0 +1,153 @@
+mod day1;
+mod day10;
+mod day11;
+mod day12;
+mod day13;
+mod day14;
+mod day15;
+mod day16;
+mod day18;
+mod day19;
+mod day2;
+mod day20;
+mod day21;
+mod day22;
+mod day3;
+mod day4;
+mod day5;
+mod day6;
+mod day7;
+mod day8;
+mod day9;
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+
+// Each day parses its input up front, then answers each part from the parsed form.
+pub trait Solution {
+    // `sample` is only needed by days whose puzzle parameters differ for the sample input.
+    fn parse(input: &str, sample: bool) -> Self
+    where
+        Self: Sized;
+
+    fn part1(&self) -> String;
+
+    // None until the second part has been solved.
+    fn part2(&self) -> Option<String> {
+        None
+    }
+}
+
+pub struct Day {
+    pub number: u8,
+    parse: fn(&str, bool) -> Box<dyn Solution>,
+}
+
+impl Day {
+    pub fn solution(&self, input: &str, sample: bool) -> Box<dyn Solution> {
+        (self.parse)(input, sample)
+    }
+
+    // The input files checked in alongside the day's solution.
+    pub fn input_path(&self, sample: bool) -> PathBuf {
+        let file_name = if sample {
+            "sample_input.txt"
+        } else {
+            "input.txt"
+        };
+        self.dir().join(file_name)
+    }
+
+    // Answers recorded in the day's answers.toml, under [sample] or [input]. Parts without a
+    // recorded answer (or days without the file at all) come back as None.
+    pub fn expected_answers(&self, sample: bool) -> Result<ExpectedAnswers> {
+        let path = self.dir().join("answers.toml");
+        if !path.exists() {
+            return Ok(ExpectedAnswers::default());
+        }
+
+        let answers: toml::Value = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+            .parse()
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        let section = if sample { "sample" } else { "input" };
+        let answer = |part: &str| match answers.get(section).and_then(|answers| answers.get(part)) {
+            None => Ok(None),
+            Some(toml::Value::String(answer)) => Ok(Some(answer.clone())),
+            Some(toml::Value::Integer(answer)) => Ok(Some(answer.to_string())),
+            Some(other) => bail!(
+                "Unexpected answer {} for {}.{} in {}",
+                other,
+                section,
+                part,
+                path.display()
+            ),
+        };
+
+        Ok(ExpectedAnswers {
+            part1: answer("part1")?,
+            part2: answer("part2")?,
+        })
+    }
+
+    fn dir(&self) -> PathBuf {
+        days_dir().join(format!("day{}", self.number))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ExpectedAnswers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+pub fn get(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+fn boxed<S: Solution + 'static>(input: &str, sample: bool) -> Box<dyn Solution> {
+    Box::new(S::parse(input, sample))
+}
+
+// Where each day's input and answers live: $AOC_DAYS_DIR if set, otherwise src/lib/days under the
+// working directory, so by default run from the repository root.
+fn days_dir() -> PathBuf {
+    env::var_os("AOC_DAYS_DIR").map_or_else(|| Path::new("src/lib/days").to_owned(), PathBuf::from)
+}
+
+macro_rules! day {
+    ($number:literal, $module:ident) => {
+        Day {
+            number: $number,
+            parse: boxed::<$module::Puzzle>,
+        }
+    };
+}
+
+pub static DAYS: &[Day] = &[
+    day!(1, day1),
+    day!(2, day2),
+    day!(3, day3),
+    day!(4, day4),
+    day!(5, day5),
+    day!(6, day6),
+    day!(7, day7),
+    day!(8, day8),
+    day!(9, day9),
+    day!(10, day10),
+    day!(11, day11),
+    day!(12, day12),
+    day!(13, day13),
+    day!(14, day14),
+    day!(15, day15),
+    day!(16, day16),
+    day!(18, day18),
+    day!(19, day19),
+    day!(20, day20),
+    day!(21, day21),
+    day!(22, day22),
+];
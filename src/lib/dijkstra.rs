@@ -1,162 +1,338 @@
 use std::{
-    cell::RefCell,
     cmp::Reverse,
     collections::{BinaryHeap, HashMap},
     hash::Hash,
 };
 
-pub fn solve<T: Clone + Eq + Hash>(
-    all_edges: &HashMap<T, Vec<(T, u64)>>,
+use crate::bfs;
+use crate::graph::{Graph, UniformEdges};
+
+pub fn solve<G: Graph<Cost = u64>>(
+    graph: &G,
+    start: G::Node,
+    end: G::Node,
+) -> Option<(Vec<G::Node>, u64)> {
+    solve_from_many(graph, [start], end)
+}
+
+// The uniform_edges variants have every edge the same length, so use a plain breadth-first search
+// rather than the heap.
+pub fn solve_uniform_edges<T: Clone + Eq + Hash>(
+    all_edges: &HashMap<T, Vec<T>>,
     start: T,
     end: T,
 ) -> Option<(Vec<T>, u64)> {
-    let mut solver = Solver::from_raw_map(all_edges);
-    solver.solve(start, end)
+    solve_from_many_uniform_edges(all_edges, [start], end)
 }
 
-pub fn solve_uniform_edges<T: Clone + Eq + Hash>(
+// As solve, but only finds the length of the shortest route, without building the route itself.
+pub fn distance<G: Graph<Cost = u64>>(graph: &G, start: G::Node, end: G::Node) -> Option<u64> {
+    Solver::new(start).solve(graph, &end)
+}
+
+pub fn distance_uniform_edges<T: Clone + Eq + Hash>(
     all_edges: &HashMap<T, Vec<T>>,
     start: T,
     end: T,
-) -> Option<(Vec<T>, u64)> {
-    solve(
-        &all_edges
-            .iter()
-            .map(|(id, edges)| {
-                (
-                    id.clone(),
-                    edges
-                        .iter()
-                        .map(|edge| (edge.clone(), 1))
-                        .collect::<Vec<_>>(),
-                )
-            })
-            .collect(),
-        start,
-        end,
-    )
+) -> Option<u64> {
+    bfs::distance(&UniformEdges(all_edges), start, end).map(|steps| steps as u64)
 }
 
-struct DijkstraNode<T: Clone + Hash> {
-    identifier: T,
-    edges: Vec<(T, u64)>,
-    explored: RefCell<bool>,
+// Shortest route to end from whichever of the starts is closest.
+pub fn solve_from_many<G: Graph<Cost = u64>>(
+    graph: &G,
+    starts: impl IntoIterator<Item = G::Node>,
+    end: G::Node,
+) -> Option<(Vec<G::Node>, u64)> {
+    let mut solver = Solver::from_starts(starts);
+    let length = solver.solve(graph, &end)?;
+    Some((solver.path_to(&end).unwrap(), length))
 }
 
-impl<T: Clone + Eq + Hash> DijkstraNode<T> {
-    pub fn new(identifier: T, edges: Vec<(T, u64)>) -> Self {
-        DijkstraNode {
-            identifier,
-            edges,
-            explored: RefCell::new(false),
-        }
-    }
+pub fn solve_from_many_uniform_edges<T: Clone + Eq + Hash>(
+    all_edges: &HashMap<T, Vec<T>>,
+    starts: impl IntoIterator<Item = T>,
+    end: T,
+) -> Option<(Vec<T>, u64)> {
+    let mut search = bfs::Search::from_starts(starts);
+    let steps = search.solve(&UniformEdges(all_edges), &end)?;
+    Some((search.path_to(&end).unwrap(), steps as u64))
 }
 
-impl<T: Clone + Eq + Hash> Hash for DijkstraNode<T> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.identifier.hash(state);
-    }
+// Every shortest route from start to end, along with their length.
+pub fn solve_all<G: Graph<Cost = u64>>(
+    graph: &G,
+    start: G::Node,
+    end: G::Node,
+) -> Option<(Vec<Vec<G::Node>>, u64)> {
+    let mut solver = Solver::new(start);
+    let length = solver.solve(graph, &end)?;
+    Some((solver.paths_to(&end), length))
 }
 
-struct DijkstraRoute<'a, T: Clone + Eq + Hash> {
-    length: u64,
-    route: Vec<T>,
-    current_node: &'a DijkstraNode<T>,
+// Number of distinct shortest routes from start to end.
+pub fn count_paths<G: Graph<Cost = u64>>(graph: &G, start: G::Node, end: G::Node) -> Option<u64> {
+    let mut solver = Solver::new(start);
+    solver.solve(graph, &end)?;
+    Some(solver.count_paths_to(&end))
 }
 
-impl<'a, T: Clone + Eq + Hash> DijkstraRoute<'a, T> {
-    fn new(starting_node: &'a DijkstraNode<T>) -> Self {
-        DijkstraRoute {
-            length: 0,
-            route: vec![starting_node.identifier.clone()],
-            current_node: starting_node,
-        }
-    }
+// Length of the shortest route from start to every node reachable from it.
+pub fn distances_from<G: Graph<Cost = u64>>(graph: &G, start: G::Node) -> HashMap<G::Node, u64> {
+    let mut solver = Solver::new(start);
+    solver.explore_all(graph);
+    solver.into_distances()
+}
 
-    fn with_extra_edge(&self, edge_length: u64, edge_end: &'a DijkstraNode<T>) -> Self {
-        let mut new_route = self.route.clone();
-        new_route.push(edge_end.identifier.clone());
-        DijkstraRoute {
-            length: self.length + edge_length,
-            route: new_route,
-            current_node: edge_end,
-        }
-    }
+pub fn distances_from_uniform_edges<T: Clone + Eq + Hash>(
+    all_edges: &HashMap<T, Vec<T>>,
+    start: T,
+) -> HashMap<T, u64> {
+    bfs::distances_from(&UniformEdges(all_edges), start)
+        .into_iter()
+        .map(|(node, steps)| (node, steps as u64))
+        .collect()
+}
+
+// A node waiting to be explored, along with the length of the best route found to it so far.
+// Routes are explored in order of estimate, the length plus a guess at how far is left to go. For
+// plain Dijkstra the guess is always 0; A* uses a heuristic.
+struct DijkstraRoute<T> {
+    length: u64,
+    estimate: u64,
+    node: T,
 }
 
-impl<T: Clone + Eq + Hash> PartialEq for DijkstraRoute<'_, T> {
+impl<T> PartialEq for DijkstraRoute<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.length == other.length
+        self.estimate == other.estimate
     }
 }
 
-impl<T: Clone + Eq + Hash> Eq for DijkstraRoute<'_, T> {}
+impl<T> Eq for DijkstraRoute<T> {}
 
-impl<T: Clone + Eq + Hash> PartialOrd for DijkstraRoute<'_, T> {
+impl<T> PartialOrd for DijkstraRoute<T> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.length.partial_cmp(&other.length)
+        Some(self.cmp(other))
     }
 }
 
-impl<T: Clone + Eq + Hash> Ord for DijkstraRoute<'_, T> {
+impl<T> Ord for DijkstraRoute<T> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.length.cmp(&other.length)
+        self.estimate.cmp(&other.estimate)
     }
 }
 
-pub struct Solver<'a, T: Clone + Eq + Hash> {
-    nodes: HashMap<T, DijkstraNode<T>>,
-    routes: BinaryHeap<Reverse<DijkstraRoute<'a, T>>>,
+// Rather than carrying whole routes around, the solver records the best distance to each node and
+// the nodes it can be reached from by a route of that length. Routes are only rebuilt from those
+// links when asked for.
+pub struct Solver<T: Clone + Eq + Hash> {
+    distances: HashMap<T, u64>,
+    predecessors: HashMap<T, Vec<T>>,
+    routes: BinaryHeap<Reverse<DijkstraRoute<T>>>,
 }
 
-impl<'a, T: Clone + Eq + Hash + 'a> Solver<'a, T> {
-    fn solve(&'a mut self, start: T, end: T) -> Option<(Vec<T>, u64)> {
-        let start_node = &self.nodes[&start];
-        self.routes.push(Reverse(DijkstraRoute::new(start_node)));
+impl<T: Clone + Eq + Hash> Solver<T> {
+    pub fn new(start: T) -> Self {
+        Self::from_starts([start])
+    }
+
+    // Search from several nodes at once, as if they were all joined to a virtual start node by
+    // edges of length 0.
+    pub fn from_starts(starts: impl IntoIterator<Item = T>) -> Self {
+        let mut solver = Solver {
+            distances: HashMap::new(),
+            predecessors: HashMap::new(),
+            routes: BinaryHeap::new(),
+        };
+        for start in starts {
+            solver.distances.insert(start.clone(), 0);
+            // Reverse for min heap.
+            solver.routes.push(Reverse(DijkstraRoute {
+                length: 0,
+                estimate: 0,
+                node: start,
+            }));
+        }
+        solver
+    }
+
+    // Explore outwards until the shortest route to end is known, returning its length.
+    pub fn solve<G: Graph<Node = T, Cost = u64>>(&mut self, graph: &G, end: &T) -> Option<u64> {
+        self.explore(graph, Some(end), |_| 0)
+    }
+
+    // As solve, but searching towards end first (A*). The heuristic estimates the remaining
+    // distance from a node to end, and must never overestimate it or the route found may not be
+    // the shortest. Keep to one heuristic for the life of the solver.
+    pub fn solve_with_heuristic<G, H>(&mut self, graph: &G, end: &T, heuristic: H) -> Option<u64>
+    where
+        G: Graph<Node = T, Cost = u64>,
+        H: Fn(&T) -> u64,
+    {
+        self.explore(graph, Some(end), heuristic)
+    }
 
+    // Explore every node reachable from the start(s), so that all distances are final.
+    pub fn explore_all<G: Graph<Node = T, Cost = u64>>(&mut self, graph: &G) {
+        self.explore(graph, None, |_| 0);
+    }
+
+    fn explore<G, H>(&mut self, graph: &G, end: Option<&T>, heuristic: H) -> Option<u64>
+    where
+        G: Graph<Node = T, Cost = u64>,
+        H: Fn(&T) -> u64,
+    {
         while let Some(Reverse(current_route)) = self.routes.pop() {
-            let current_node = current_route.current_node;
-            if current_node.identifier == end {
-                return Some((current_route.route, current_route.length));
+            // Stale entry, a shorter route to this node has already been explored
+            if current_route.length > self.distances[&current_route.node] {
+                continue;
+            }
+            if Some(&current_route.node) == end {
+                return Some(current_route.length);
             }
 
-            if !current_node.explored.borrow().clone() {
-                for (edge_end, edge_length) in &current_route.current_node.edges {
-                    self.routes.push(
-                        // Reverse for min heap.
-                        Reverse(
-                            current_route.with_extra_edge(*edge_length, &self.nodes[&edge_end]),
-                        ),
-                    );
+            for (edge_end, edge_length) in graph.neighbours(&current_route.node) {
+                let length = current_route.length + edge_length;
+                match self.distances.get(&edge_end) {
+                    Some(best) if *best < length => continue,
+                    Some(best) if *best == length => {
+                        // Another route just as short, which only needs remembering. Ties along
+                        // zero length edges are left out, as they could link nodes in a loop.
+                        if edge_length > 0 {
+                            self.predecessors
+                                .get_mut(&edge_end)
+                                .unwrap()
+                                .push(current_route.node.clone());
+                        }
+                        continue;
+                    }
+                    _ => (),
                 }
+                self.distances.insert(edge_end.clone(), length);
+                self.predecessors
+                    .insert(edge_end.clone(), vec![current_route.node.clone()]);
+                self.routes.push(Reverse(DijkstraRoute {
+                    length,
+                    estimate: length + heuristic(&edge_end),
+                    node: edge_end,
+                }));
             }
-            *current_node.explored.borrow_mut() = true;
         }
 
         // Did not find a route
         None
     }
 
-    fn from_raw_map(all_edges: &HashMap<T, Vec<(T, u64)>>) -> Self {
-        Solver {
-            nodes: all_edges
+    // Length of the best route found so far to node. Only guaranteed to be the shortest once
+    // solve has returned for that node.
+    pub fn distance(&self, node: &T) -> Option<u64> {
+        self.distances.get(node).copied()
+    }
+
+    pub fn into_distances(self) -> HashMap<T, u64> {
+        self.distances
+    }
+
+    // Follow the predecessor links back from node to build the route to it, start first.
+    pub fn path_to(&self, node: &T) -> Option<Vec<T>> {
+        self.distances.get(node)?;
+        let mut path = vec![node.clone()];
+        while let Some(previous) = self.predecessors.get(path.last().unwrap()) {
+            path.push(previous[0].clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    // The nodes node can be reached from on a shortest route, i.e. its incoming edges in the DAG
+    // of all shortest routes. Empty for the start(s).
+    pub fn predecessors(&self, node: &T) -> &[T] {
+        self.predecessors.get(node).map_or(&[], Vec::as_slice)
+    }
+
+    // Every route to node which is as short as the best one, each start first. There can be a
+    // great many of these, so check count_paths_to first on anything large.
+    //
+    // Like distance, these are only complete once solve has returned for node, and then only for
+    // plain Dijkstra. A* stops as soon as it has one shortest route, so may have missed others.
+    pub fn paths_to(&self, node: &T) -> Vec<Vec<T>> {
+        if !self.distances.contains_key(node) {
+            return vec![];
+        }
+        // Routes are built backwards from node on a stack rather than by recursion, as they can be
+        // far longer than the call stack allows. Predecessors go on in reverse so that the routes
+        // still come out grouped by the first of them.
+        let mut paths = vec![];
+        let mut partial = vec![vec![node.clone()]];
+        while let Some(mut path) = partial.pop() {
+            let Some((first, others)) = self.predecessors(path.last().unwrap()).split_first()
+            else {
+                path.reverse();
+                paths.push(path);
+                continue;
+            };
+            for previous in others.iter().rev() {
+                let mut longer = path.clone();
+                longer.push(previous.clone());
+                partial.push(longer);
+            }
+            // The path so far is only copied where routes branch
+            path.push(first.clone());
+            partial.push(path);
+        }
+        paths
+    }
+
+    // Number of distinct shortest routes to node, without building them. This grows very quickly,
+    // e.g. past u64::MAX across an open 35x35 grid, so saturates at u64::MAX rather than
+    // overflowing.
+    pub fn count_paths_to(&self, node: &T) -> u64 {
+        if !self.distances.contains_key(node) {
+            return 0;
+        }
+        // Each node's count is the sum over its predecessors, so work back from node with an
+        // explicit stack, only counting a node once all its predecessors have been
+        let mut counts: HashMap<T, u64> = HashMap::new();
+        let mut to_count = vec![node.clone()];
+        while let Some(current) = to_count.last() {
+            if counts.contains_key(current) {
+                to_count.pop();
+                continue;
+            }
+            let previous = self.predecessors(current);
+            let uncounted: Vec<T> = previous
                 .iter()
-                .map(|(id, edges)| (id.clone(), DijkstraNode::new(id.clone(), edges.clone())))
-                .collect::<HashMap<T, DijkstraNode<T>>>(),
-            routes: BinaryHeap::new(),
+                .filter(|previous| !counts.contains_key(*previous))
+                .cloned()
+                .collect();
+            if !uncounted.is_empty() {
+                to_count.extend(uncounted);
+                continue;
+            }
+            let count = if previous.is_empty() {
+                1
+            } else {
+                previous.iter().fold(0u64, |total, previous| {
+                    total.saturating_add(counts[previous])
+                })
+            };
+            let current = to_count.pop().unwrap();
+            counts.insert(current, count);
         }
+        counts[node]
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::graph;
     use test;
 
     #[test]
     fn simple_dijkstra() {
-        let dijkstra_state = [
+        let dijkstra_state: HashMap<_, _> = [
             (1, vec![(2, 1), (3, 4)]),
             (2, vec![(1, 2), (3, 2)]),
             (3, vec![]),
@@ -165,6 +341,8 @@ mod tests {
         .collect();
         let dijkstra_sln = solve(&dijkstra_state, 1, 3);
         assert_eq!(Some((vec![1, 2, 3], 3)), dijkstra_sln);
+        assert_eq!(Some(3), distance(&dijkstra_state, 1, 3));
+        assert_eq!(None, distance(&dijkstra_state, 3, 1));
     }
 
     #[test]
@@ -175,4 +353,102 @@ mod tests {
         let dijkstra_sln = solve_uniform_edges(&dijkstra_state, 1, 3);
         assert_eq!(Some((vec![1, 3], 1)), dijkstra_sln);
     }
+
+    #[test]
+    fn many_starts() {
+        let dijkstra_state: HashMap<_, _> = [
+            (1, vec![(3, 5)]),
+            (2, vec![(3, 2), (4, 1)]),
+            (4, vec![(3, 2)]),
+            (3, vec![]),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            Some((vec![2, 3], 2)),
+            solve_from_many(&dijkstra_state, [1, 2], 3)
+        );
+        assert_eq!(None, solve_from_many(&dijkstra_state, [3], 1));
+    }
+
+    #[test]
+    fn all_distances() {
+        let dijkstra_state = [(1, vec![2, 3]), (2, vec![4]), (3, vec![]), (5, vec![1])]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            HashMap::from([(1, 0), (2, 1), (3, 1), (4, 2)]),
+            distances_from_uniform_edges(&dijkstra_state, 1)
+        );
+    }
+
+    #[test]
+    fn implicit_graph() {
+        // Reach 10 from 1 by doubling or adding one, where adding one costs 2
+        let steps = graph::from_fn(|n: &u32| [(n * 2, 1), (n + 1, 2)]);
+        assert_eq!(Some((vec![1, 2, 4, 5, 10], 5)), solve(&steps, 1, 10));
+    }
+
+    #[test]
+    fn tied_routes() {
+        // Two equal routes to 4, then one onwards to 5, plus a longer way round
+        let dijkstra_state: HashMap<_, _> = [
+            (1, vec![(2, 1), (3, 2)]),
+            (2, vec![(4, 2)]),
+            (3, vec![(4, 1)]),
+            (4, vec![(5, 1)]),
+            (5, vec![]),
+            (6, vec![(5, 1)]),
+        ]
+        .into_iter()
+        .collect();
+        let (mut paths, length) = solve_all(&dijkstra_state, 1, 5).unwrap();
+        paths.sort_unstable();
+        assert_eq!(vec![vec![1, 2, 4, 5], vec![1, 3, 4, 5]], paths);
+        assert_eq!(4, length);
+        assert_eq!(Some(2), count_paths(&dijkstra_state, 1, 5));
+        assert_eq!(None, count_paths(&dijkstra_state, 5, 1));
+
+        let mut solver = Solver::new(1);
+        solver.explore_all(&dijkstra_state);
+        assert_eq!(0, solver.count_paths_to(&6));
+        assert_eq!(1, solver.count_paths_to(&1));
+        assert!(solver.predecessors(&1).is_empty());
+    }
+
+    #[test]
+    fn counting_grid_paths() {
+        // Routes across a size x size grid moving only right or down: 2(size - 1) choose size - 1
+        let grid = |size| {
+            graph::from_fn(move |&(x, y): &(u8, u8)| {
+                [(x + 1, y), (x, y + 1)]
+                    .into_iter()
+                    .filter(move |&(x, y)| x < size && y < size)
+                    .map(|pos| (pos, 1))
+            })
+        };
+        assert_eq!(Some(6), count_paths(&grid(3), (0, 0), (2, 2)));
+        assert_eq!(
+            Some(7_219_428_434_016_265_740),
+            count_paths(&grid(34), (0, 0), (33, 33))
+        );
+        assert_eq!(Some(u64::MAX), count_paths(&grid(35), (0, 0), (34, 34)));
+    }
+
+    #[test]
+    fn long_route() {
+        // Far more steps than there is room for stack frames
+        let line = graph::from_fn(|n: &u32| (*n < 100_000).then_some((n + 1, 1)));
+        let (paths, length) = solve_all(&line, 0, 100_000).unwrap();
+        assert_eq!(100_000, length);
+        assert_eq!(1, paths.len());
+        assert_eq!(100_001, paths[0].len());
+        assert_eq!(Some(1), count_paths(&line, 0, 100_000));
+    }
+
+    #[test]
+    fn start_is_end() {
+        let dijkstra_state: HashMap<_, _> = [(1, vec![(2, 1)]), (2, vec![])].into_iter().collect();
+        assert_eq!(Some((vec![1], 0)), solve(&dijkstra_state, 1, 1));
+    }
 }
@@ -0,0 +1,454 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    iter::Peekable,
+    str::{Chars, FromStr},
+};
+
+use anyhow::{bail, Context, Result};
+use num::{rational::Ratio, One, Zero};
+
+use crate::op_wrapper::Op;
+
+// Exact fractions, so that division never loses anything along the way.
+pub type Rational = Ratio<i128>;
+
+// An arithmetic expression, as a tree of Ops over numbers and named unknowns.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Const(Rational),
+    Var(String),
+    Op(Op, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    pub fn constant(value: i128) -> Self {
+        Expr::Const(Rational::from_integer(value))
+    }
+
+    pub fn var(name: &str) -> Self {
+        Expr::Var(name.to_owned())
+    }
+
+    pub fn op(op: Op, left: Expr, right: Expr) -> Self {
+        Expr::Op(op, Box::new(left), Box::new(right))
+    }
+
+    pub fn contains_var(&self, name: &str) -> bool {
+        match self {
+            Expr::Const(_) => false,
+            Expr::Var(var) => var == name,
+            Expr::Op(_, left, right) => left.contains_var(name) || right.contains_var(name),
+        }
+    }
+
+    // Work out the value, given values for any unknowns. Fails on an unknown with no value, or
+    // division by zero.
+    pub fn eval(&self, vars: &HashMap<String, Rational>) -> Result<Rational> {
+        self.eval_with(&|name| vars.get(name).copied())
+    }
+
+    // As eval, but looking up unknowns with a function, to avoid building a map for each
+    // evaluation. Also fails on overflow.
+    pub fn eval_with(&self, vars: &impl Fn(&str) -> Option<Rational>) -> Result<Rational> {
+        match self {
+            Expr::Const(value) => Ok(*value),
+            Expr::Var(name) => vars(name).with_context(|| format!("No value for {}", name)),
+            Expr::Op(op, left, right) => {
+                op.checked_call(left.eval_with(vars)?, right.eval_with(vars)?)
+            }
+        }
+    }
+
+    // Replace every unknown with the expression given for it.
+    pub fn substitute(&self, values: &mut impl FnMut(&str) -> Expr) -> Expr {
+        match self {
+            Expr::Const(_) => self.clone(),
+            Expr::Var(name) => values(name),
+            Expr::Op(op, left, right) => {
+                Expr::op(*op, left.substitute(values), right.substitute(values))
+            }
+        }
+    }
+
+    // Work out every part which doesn't depend on an unknown, and drop operations which do
+    // nothing, like adding 0 or multiplying by 1. Fails on division by zero.
+    pub fn simplify(&self) -> Result<Expr> {
+        let Expr::Op(op, left, right) = self else {
+            return Ok(self.clone());
+        };
+        let (left, right) = (left.simplify()?, right.simplify()?);
+        let is = |expr: &Expr, value: Rational| *expr == Expr::Const(value);
+        let (zero, one) = (Rational::zero(), Rational::one());
+
+        Ok(match (op, left, right) {
+            (op, Expr::Const(left), Expr::Const(right)) => {
+                Expr::Const(op.checked_call(left, right)?)
+            }
+            (Op::Add, left, right) if is(&left, zero) => right,
+            (Op::Add | Op::Sub, left, right) if is(&right, zero) => left,
+            (Op::Mul, left, right) if is(&left, one) => right,
+            (Op::Mul | Op::Div, left, right) if is(&right, one) => left,
+            (op, left, right) => Expr::op(*op, left, right),
+        })
+    }
+}
+
+// Find the value of var which makes both sides equal, by undoing each operation around it in turn.
+// var must appear exactly once, on one side or the other. Like eval, fails on overflow.
+pub fn solve(left: &Expr, right: &Expr, var: &str) -> Result<Rational> {
+    let (mut unknown, known) = match (left.contains_var(var), right.contains_var(var)) {
+        (true, false) => (left.simplify()?, right),
+        (false, true) => (right.simplify()?, left),
+        (true, true) => bail!("{} is on both sides of {} = {}", var, left, right),
+        (false, false) => bail!("{} is not in {} = {}", var, left, right),
+    };
+    let mut value = known
+        .simplify()?
+        .eval(&HashMap::new())
+        .context("Other side of the equation has another unknown")?;
+
+    loop {
+        let Expr::Op(op, op_left, op_right) = unknown else {
+            // Nothing left around the unknown
+            return match unknown {
+                Expr::Var(_) => Ok(value),
+                _ => bail!("{} was simplified away", var),
+            };
+        };
+        let empty = HashMap::new();
+        match (op_left.contains_var(var), op_right.contains_var(var)) {
+            // value = unknown op constant
+            (true, false) => {
+                let constant = op_right.eval(&empty)?;
+                value = match op {
+                    Op::Add => Op::Sub.checked_call(value, constant)?,
+                    Op::Sub => Op::Add.checked_call(value, constant)?,
+                    Op::Mul if constant.is_zero() => return zero_product(var, value),
+                    Op::Mul => Op::Div.checked_call(value, constant)?,
+                    Op::Div if constant.is_zero() => bail!("{} is divided by 0", var),
+                    Op::Div => Op::Mul.checked_call(value, constant)?,
+                    op => bail!("Can't undo {} around {}", op, var),
+                };
+                unknown = *op_left;
+            }
+            // value = constant op unknown
+            (false, true) => {
+                let constant = op_left.eval(&empty)?;
+                value = match op {
+                    Op::Add => Op::Sub.checked_call(value, constant)?,
+                    Op::Sub => Op::Sub.checked_call(constant, value)?,
+                    Op::Mul if constant.is_zero() => return zero_product(var, value),
+                    Op::Mul => Op::Div.checked_call(value, constant)?,
+                    // 0 / var is 0 whatever var is, as long as it isn't 0 itself
+                    Op::Div if value.is_zero() && constant.is_zero() => {
+                        bail!("{} can be anything but 0", var)
+                    }
+                    Op::Div if value.is_zero() || constant.is_zero() => {
+                        bail!("No value of {} gives {}", var, value)
+                    }
+                    Op::Div => Op::Div.checked_call(constant, value)?,
+                    op => bail!("Can't undo {} around {}", op, var),
+                };
+                unknown = *op_right;
+            }
+            _ => bail!("{} appears more than once", var),
+        }
+    }
+}
+
+// Multiplying by 0 loses var entirely, so the equation either always or never holds
+fn zero_product(var: &str, value: Rational) -> Result<Rational> {
+    if value.is_zero() {
+        bail!("{} can be anything", var)
+    } else {
+        bail!("No value of {} gives {}", var, value)
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Const(value) => write!(f, "{}", value),
+            Expr::Var(name) => write!(f, "{}", name),
+            Expr::Op(op, left, right) if op.precedence().is_none() => {
+                write!(f, "{}({}, {})", op, left, right)
+            }
+            Expr::Op(op, left, right) => write!(f, "({} {} {})", left, op, right),
+        }
+    }
+}
+
+// Infix arithmetic like "old * (old + 3)", with the usual precedence: ^ first, then * / %, then
+// + -, then comparisons. min and max are written as calls, like min(a, b).
+impl FromStr for Expr {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parser = Parser {
+            tokens: tokenise(s)?.into_iter().peekable(),
+        };
+        let expr = parser.expr(0)?;
+        if let Some(token) = parser.tokens.next() {
+            bail!("Unexpected {:?} in {}", token, s);
+        }
+        Ok(expr)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Num(i128),
+    Name(String),
+    Op(Op),
+    Open,
+    Close,
+    Comma,
+}
+
+fn tokenise(s: &str) -> Result<Vec<Token>> {
+    let mut chars = s.chars().peekable();
+    let mut tokens = vec![];
+    while let Some(&c) = chars.peek() {
+        let token = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '0'..='9' => Token::Num(take_while(&mut chars, |c| c.is_ascii_digit()).parse()?),
+            c if c.is_alphabetic() || c == '_' => {
+                Token::Name(take_while(&mut chars, |c| c.is_alphanumeric() || c == '_'))
+            }
+            '(' | ')' | ',' => {
+                chars.next();
+                match c {
+                    '(' => Token::Open,
+                    ')' => Token::Close,
+                    _ => Token::Comma,
+                }
+            }
+            _ => {
+                let op = take_while(&mut chars, |c| "+-*/%^=!<>".contains(c));
+                // Split runs like "*-" where the second op is a minus sign
+                let op = match op.parse() {
+                    Ok(op) => op,
+                    Err(_) if op.len() > 1 && op.ends_with('-') => {
+                        tokens.push(Token::Op(op[..op.len() - 1].parse()?));
+                        Op::Sub
+                    }
+                    Err(err) => return Err(err).with_context(|| format!("Bad {:?} in {}", op, s)),
+                };
+                Token::Op(op)
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn take_while(chars: &mut Peekable<Chars>, matches: impl Fn(char) -> bool) -> String {
+    let mut taken = String::new();
+    while let Some(c) = chars.next_if(|c| matches(*c)) {
+        taken.push(c);
+    }
+    taken
+}
+
+// Precedence climbing: each call takes operands joined by ops binding at least as tightly as
+// min_precedence.
+struct Parser<I: Iterator<Item = Token>> {
+    tokens: Peekable<I>,
+}
+
+impl<I: Iterator<Item = Token>> Parser<I> {
+    fn expr(&mut self, min_precedence: u8) -> Result<Expr> {
+        let mut left = self.operand()?;
+        while let Some(&Token::Op(op)) = self.tokens.peek() {
+            let Some(precedence) = op.precedence() else {
+                bail!("{} must be called like {}(a, b)", op, op);
+            };
+            if precedence < min_precedence {
+                break;
+            }
+            self.tokens.next();
+            let right_precedence = if op.is_right_associative() {
+                precedence
+            } else {
+                precedence + 1
+            };
+            left = Expr::op(op, left, self.expr(right_precedence)?);
+        }
+        Ok(left)
+    }
+
+    fn operand(&mut self) -> Result<Expr> {
+        match self.tokens.next() {
+            Some(Token::Num(value)) => Ok(Expr::constant(value)),
+            // A minus sign binds tighter than everything but ^, so -x^2 is -(x^2)
+            Some(Token::Op(Op::Sub)) => Ok(match self.expr(Op::Pow.precedence().unwrap())? {
+                Expr::Const(value) => Expr::Const(-value),
+                expr => Expr::op(Op::Sub, Expr::constant(0), expr),
+            }),
+            Some(Token::Open) => {
+                let expr = self.expr(0)?;
+                self.expect(Token::Close)?;
+                Ok(expr)
+            }
+            Some(Token::Name(name)) if self.tokens.peek() == Some(&Token::Open) => {
+                let op: Op = name.parse()?;
+                if op.precedence().is_some() {
+                    bail!("{} can't be called", op);
+                }
+                self.tokens.next();
+                let left = self.expr(0)?;
+                self.expect(Token::Comma)?;
+                let right = self.expr(0)?;
+                self.expect(Token::Close)?;
+                Ok(Expr::op(op, left, right))
+            }
+            Some(Token::Name(name)) => Ok(Expr::Var(name)),
+            token => bail!("Expected a number, name or bracket, found {:?}", token),
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        match self.tokens.next() {
+            Some(token) if token == expected => Ok(()),
+            token => bail!("Expected {:?}, found {:?}", expected, token),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn x() -> Expr {
+        Expr::var("x")
+    }
+
+    fn c(value: i128) -> Expr {
+        Expr::constant(value)
+    }
+
+    #[test]
+    fn evaluation() {
+        // (4 + 2 * (x - 3)) / 4
+        let expr = Expr::op(
+            Op::Div,
+            Expr::op(
+                Op::Add,
+                c(4),
+                Expr::op(Op::Mul, c(2), Expr::op(Op::Sub, x(), c(3))),
+            ),
+            c(4),
+        );
+        let vars = HashMap::from([("x".to_owned(), Rational::from_integer(4))]);
+        assert_eq!(Rational::new(3, 2), expr.eval(&vars).unwrap());
+        assert!(expr.eval(&HashMap::new()).is_err());
+        assert!(Expr::op(Op::Div, c(1), c(0)).eval(&vars).is_err());
+        assert_eq!("((4 + (2 * (x - 3))) / 4)", expr.to_string());
+    }
+
+    #[test]
+    fn simplification() {
+        let expr = Expr::op(
+            Op::Add,
+            Expr::op(Op::Mul, Expr::op(Op::Sub, c(3), c(2)), x()),
+            Expr::op(Op::Div, c(0), c(5)),
+        );
+        assert_eq!(x(), expr.simplify().unwrap());
+        assert_eq!(c(2), Expr::op(Op::Div, c(4), c(2)).simplify().unwrap());
+    }
+
+    #[test]
+    fn solving() {
+        // 10 = 4 + 2 * (x - 3) gives x = 6
+        let expr = Expr::op(
+            Op::Add,
+            c(4),
+            Expr::op(Op::Mul, c(2), Expr::op(Op::Sub, x(), c(3))),
+        );
+        assert_eq!(
+            Rational::from_integer(6),
+            solve(&c(10), &expr, "x").unwrap()
+        );
+
+        // 3 / x = 2 gives x = 3/2
+        let expr = Expr::op(Op::Div, c(3), x());
+        assert_eq!(Rational::new(3, 2), solve(&expr, &c(2), "x").unwrap());
+
+        assert!(solve(&Expr::op(Op::Mul, x(), x()), &c(4), "x").is_err());
+        let error = |left: Expr, right: Expr| solve(&left, &right, "x").unwrap_err().to_string();
+        assert_eq!(
+            "No value of x gives 4",
+            error(Expr::op(Op::Mul, x(), c(0)), c(4))
+        );
+        assert_eq!(
+            "x can be anything",
+            error(Expr::op(Op::Mul, c(0), x()), c(0))
+        );
+        assert_eq!(
+            "x is divided by 0",
+            error(Expr::op(Op::Div, x(), c(0)), c(5))
+        );
+        assert_eq!(
+            "No value of x gives 0",
+            error(Expr::op(Op::Div, c(3), x()), c(0))
+        );
+        assert_eq!(
+            "No value of x gives 5",
+            error(Expr::op(Op::Div, c(0), x()), c(5))
+        );
+        assert_eq!(
+            "x can be anything but 0",
+            error(Expr::op(Op::Div, c(0), x()), c(0))
+        );
+
+        // x + -max = max needs x = 2 * max, which doesn't fit
+        let overflowing = Expr::op(Op::Add, x(), c(-i128::MAX));
+        assert!(error(overflowing, c(i128::MAX)).starts_with("Overflow"));
+        let overflowing = Expr::op(Op::Div, x(), c(i128::MAX));
+        assert!(error(overflowing, c(2)).starts_with("Overflow"));
+        assert!(solve(&Expr::var("y"), &c(4), "x").is_err());
+        assert!(solve(&Expr::op(Op::Rem, x(), c(3)), &c(1), "x").is_err());
+    }
+
+    #[test]
+    fn parsing() {
+        let parse = |s: &str| s.parse::<Expr>().unwrap();
+        assert_eq!(
+            Expr::op(Op::Mul, Expr::var("old"), c(19)),
+            parse("old * 19")
+        );
+        assert_eq!(
+            "((1 + (2 * (3 ^ (2 ^ 2)))) - 4)",
+            parse("1 + 2 * 3 ^ 2 ^ 2 - 4").to_string()
+        );
+        assert_eq!("((1 - 2) - 3)", parse("1 - 2 - 3").to_string());
+        assert_eq!("((2 * (x + 1)) == 8)", parse("2*(x+1) == 8").to_string());
+        assert_eq!("(0 - (x ^ 2))", parse("-x^2").to_string());
+        assert_eq!("(3 * -2)", parse("3*-2").to_string());
+        assert_eq!(
+            "max(min(x, 3), (y % 4))",
+            parse("max(min(x, 3), y % 4)").to_string()
+        );
+        assert_eq!(
+            Rational::from_integer(1),
+            parse("7 % 3 < 2").eval(&HashMap::new()).unwrap()
+        );
+
+        for bad in [
+            "",
+            "1 +",
+            "(1",
+            "1 2",
+            "1 = 2",
+            "foo(1, 2)",
+            "x min 2",
+            "+(1, 2)",
+        ] {
+            assert!(bad.parse::<Expr>().is_err(), "{}", bad);
+        }
+    }
+}
@@ -0,0 +1,351 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+use crate::graph::FiniteGraph;
+
+// A directed network of pipes with capacities, for maximum flow and minimum cut problems. Nodes can
+// be any type; internally they are numbered in the order they are first seen.
+pub struct FlowNetwork<T: Clone + Eq + Hash> {
+    index: HashMap<T, usize>,
+    nodes: Vec<T>,
+    // Every edge is stored next to its reverse, so edge i ^ 1 is the reverse of edge i. Reverses
+    // have no capacity of their own, but flow along an edge gives its reverse room to send that
+    // flow back, which is how flow gets rerouted.
+    edges: Vec<FlowEdge>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+struct FlowEdge {
+    to: usize,
+    capacity: u64,
+    // Capacity left, including any flow that could be sent back the other way
+    residual: u64,
+}
+
+impl FlowEdge {
+    fn flow(&self) -> u64 {
+        self.capacity - self.residual
+    }
+}
+
+impl<T: Clone + Eq + Hash> FlowNetwork<T> {
+    pub fn new() -> Self {
+        FlowNetwork {
+            index: HashMap::new(),
+            nodes: vec![],
+            edges: vec![],
+            adjacency: vec![],
+        }
+    }
+
+    // Every edge of the graph, with its length used as its capacity.
+    pub fn from_graph<G: FiniteGraph<Node = T, Cost = u64>>(graph: &G) -> Self {
+        let mut network = Self::new();
+        for node in graph.nodes() {
+            network.node(&node);
+            for (edge_end, capacity) in graph.neighbours(&node) {
+                network.add_edge(&node, &edge_end, capacity);
+            }
+        }
+        network
+    }
+
+    fn node(&mut self, node: &T) -> usize {
+        if let Some(&i) = self.index.get(node) {
+            return i;
+        }
+        self.index.insert(node.clone(), self.nodes.len());
+        self.nodes.push(node.clone());
+        self.adjacency.push(vec![]);
+        self.nodes.len() - 1
+    }
+
+    // Parallel edges are allowed, and add together.
+    pub fn add_edge(&mut self, from: &T, to: &T, capacity: u64) {
+        let (from, to) = (self.node(from), self.node(to));
+        self.adjacency[from].push(self.edges.len());
+        self.edges.push(FlowEdge {
+            to,
+            capacity,
+            residual: capacity,
+        });
+        self.adjacency[to].push(self.edges.len());
+        self.edges.push(FlowEdge {
+            to: from,
+            capacity: 0,
+            residual: 0,
+        });
+    }
+
+    // Push as much flow as possible from source to sink using Dinic's algorithm, returning the
+    // amount added. Can be called again after adding more edges to top up the flow. Panics if the
+    // total added doesn't fit in a u64.
+    pub fn max_flow(&mut self, source: &T, sink: &T) -> u64 {
+        let (Some(&source), Some(&sink)) = (self.index.get(source), self.index.get(sink)) else {
+            return 0;
+        };
+        if source == sink {
+            return 0;
+        }
+
+        let mut total: u64 = 0;
+        // Each phase only pushes flow along shortest routes, given by the distance (level) of each
+        // node from the source through edges with capacity left.
+        while let Some(levels) = self.levels(source, sink) {
+            // Position in each node's adjacency list, so edges found to be full aren't retried
+            let mut next_edge = vec![0; self.nodes.len()];
+            loop {
+                let pushed = self.augment(source, sink, &levels, &mut next_edge);
+                if pushed == 0 {
+                    break;
+                }
+                total = total
+                    .checked_add(pushed)
+                    .expect("Total flow overflowed a u64");
+            }
+        }
+        total
+    }
+
+    fn levels(&self, source: usize, sink: usize) -> Option<Vec<Option<usize>>> {
+        let mut levels = vec![None; self.nodes.len()];
+        levels[source] = Some(0);
+        let mut to_explore = VecDeque::from([source]);
+        while let Some(node) = to_explore.pop_front() {
+            for &edge in &self.adjacency[node] {
+                let edge = &self.edges[edge];
+                if edge.residual > 0 && levels[edge.to].is_none() {
+                    levels[edge.to] = Some(levels[node].unwrap() + 1);
+                    to_explore.push_back(edge.to);
+                }
+            }
+        }
+        levels[sink].map(|_| levels)
+    }
+
+    // Find a route from source to sink going one level further each step, and push as much as it
+    // can take. The route is kept as a stack of edges, so long routes can't overflow the call stack.
+    fn augment(
+        &mut self,
+        source: usize,
+        sink: usize,
+        levels: &[Option<usize>],
+        next_edge: &mut [usize],
+    ) -> u64 {
+        let mut route: Vec<usize> = vec![];
+        let mut node = source;
+        loop {
+            if node == sink {
+                let pushed = route
+                    .iter()
+                    .map(|edge| self.edges[*edge].residual)
+                    .min()
+                    .unwrap();
+                for &edge in &route {
+                    self.edges[edge].residual -= pushed;
+                    self.edges[edge ^ 1].residual += pushed;
+                }
+                return pushed;
+            }
+            if next_edge[node] < self.adjacency[node].len() {
+                let edge = self.adjacency[node][next_edge[node]];
+                let FlowEdge { to, residual, .. } = self.edges[edge];
+                if residual > 0 && levels[to] == levels[node].map(|level| level + 1) {
+                    route.push(edge);
+                    node = to;
+                    continue;
+                }
+                next_edge[node] += 1;
+            } else {
+                // A dead end, so back up and don't try the edge which led here again
+                let Some(edge) = route.pop() else {
+                    return 0;
+                };
+                node = self.edges[edge ^ 1].to;
+                next_edge[node] += 1;
+            }
+        }
+    }
+
+    // Flow currently going directly from one node to another.
+    pub fn flow(&self, from: &T, to: &T) -> u64 {
+        let (Some(&from), Some(&to)) = (self.index.get(from), self.index.get(to)) else {
+            return 0;
+        };
+        self.adjacency[from]
+            .iter()
+            .filter(|edge| *edge % 2 == 0 && self.edges[**edge].to == to)
+            .map(|edge| self.edges[*edge].flow())
+            .sum()
+    }
+
+    // After max_flow, the nodes still reachable from source, and the edges leading out of them,
+    // which together make a cut of minimum total capacity.
+    pub fn min_cut(&self, source: &T) -> (HashSet<T>, Vec<(T, T, u64)>) {
+        let Some(&source) = self.index.get(source) else {
+            return (HashSet::new(), vec![]);
+        };
+        let mut reachable = vec![false; self.nodes.len()];
+        reachable[source] = true;
+        let mut to_explore = vec![source];
+        while let Some(node) = to_explore.pop() {
+            for &edge in &self.adjacency[node] {
+                let edge = &self.edges[edge];
+                if edge.residual > 0 && !reachable[edge.to] {
+                    reachable[edge.to] = true;
+                    to_explore.push(edge.to);
+                }
+            }
+        }
+
+        let cut = (0..self.nodes.len())
+            .filter(|node| reachable[*node])
+            .flat_map(|node| self.adjacency[node].iter().map(move |edge| (node, *edge)))
+            .filter(|(_, edge)| edge % 2 == 0 && !reachable[self.edges[*edge].to])
+            .map(|(node, edge)| {
+                let edge = &self.edges[edge];
+                (
+                    self.nodes[node].clone(),
+                    self.nodes[edge.to].clone(),
+                    edge.capacity,
+                )
+            })
+            .collect();
+        let side = (0..self.nodes.len())
+            .filter(|node| reachable[*node])
+            .map(|node| self.nodes[node].clone())
+            .collect();
+        (side, cut)
+    }
+}
+
+impl<T: Clone + Eq + Hash> Default for FlowNetwork<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum MatchingNode<L, R> {
+    Source,
+    Sink,
+    Left(L),
+    Right(R),
+}
+
+// Pair up as many left items as possible with right items they are allowed to go with, using each
+// item at most once. Options listed more than once for the same left item count once.
+pub fn bipartite_matching<L, R>(allowed: &HashMap<L, Vec<R>>) -> Vec<(L, R)>
+where
+    L: Clone + Eq + Hash,
+    R: Clone + Eq + Hash,
+{
+    use MatchingNode::*;
+
+    let allowed: Vec<(&L, HashSet<&R>)> = allowed
+        .iter()
+        .map(|(left, options)| (left, options.iter().collect()))
+        .collect();
+    let mut network = FlowNetwork::new();
+    let mut rights = HashSet::new();
+    for (left, options) in &allowed {
+        network.add_edge(&Source, &Left((*left).clone()), 1);
+        for right in options {
+            network.add_edge(&Left((*left).clone()), &Right((*right).clone()), 1);
+            rights.insert(*right);
+        }
+    }
+    for right in rights {
+        network.add_edge(&Right(right.clone()), &Sink, 1);
+    }
+    network.max_flow(&Source, &Sink);
+
+    allowed
+        .iter()
+        .flat_map(|(left, options)| options.iter().map(move |right| (*left, *right)))
+        .filter(|(left, right)| network.flow(&Left((*left).clone()), &Right((*right).clone())) > 0)
+        .map(|(left, right)| (left.clone(), right.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_flow_and_cut() {
+        // The classic example from CLRS
+        let mut network = FlowNetwork::new();
+        for (from, to, capacity) in [
+            ("s", "v1", 16),
+            ("s", "v2", 13),
+            ("v2", "v1", 4),
+            ("v1", "v3", 12),
+            ("v3", "v2", 9),
+            ("v2", "v4", 14),
+            ("v4", "v3", 7),
+            ("v3", "t", 20),
+            ("v4", "t", 4),
+        ] {
+            network.add_edge(&from, &to, capacity);
+        }
+        assert_eq!(23, network.max_flow(&"s", &"t"));
+        assert_eq!(0, network.max_flow(&"s", &"t"));
+
+        let (side, cut) = network.min_cut(&"s");
+        assert_eq!(HashSet::from(["s", "v1", "v2", "v4"]), side);
+        assert_eq!(23, cut.iter().map(|(_, _, capacity)| capacity).sum::<u64>());
+        assert_eq!(0, network.max_flow(&"s", &"nowhere"));
+    }
+
+    #[test]
+    fn from_graph() {
+        let graph: HashMap<_, _> = [
+            (1, vec![(2, 3), (3, 2)]),
+            (2, vec![(3, 5), (4, 2)]),
+            (3, vec![(4, 3)]),
+        ]
+        .into_iter()
+        .collect();
+        let mut network = FlowNetwork::from_graph(&graph);
+        assert_eq!(5, network.max_flow(&1, &4));
+        assert_eq!(2, network.flow(&2, &4));
+    }
+
+    #[test]
+    fn matching() {
+        let allowed = HashMap::from([
+            ("alice", vec![1, 2]),
+            ("bob", vec![1]),
+            ("carol", vec![2, 3]),
+            ("dave", vec![3]),
+        ]);
+        let matching = bipartite_matching(&allowed);
+        assert_eq!(3, matching.len());
+        let lefts: HashSet<_> = matching.iter().map(|(left, _)| left).collect();
+        let rights: HashSet<_> = matching.iter().map(|(_, right)| right).collect();
+        assert_eq!(3, lefts.len());
+        assert_eq!(3, rights.len());
+        for (left, right) in &matching {
+            assert!(allowed[left].contains(right));
+        }
+
+        // Repeated options don't give repeated pairs
+        let allowed = HashMap::from([("alice", vec![1, 1, 1]), ("bob", vec![2, 2])]);
+        let mut matching = bipartite_matching(&allowed);
+        matching.sort_unstable();
+        assert_eq!(vec![("alice", 1), ("bob", 2)], matching);
+    }
+
+    #[test]
+    fn long_route() {
+        // Deep enough that a recursive search would overflow the stack
+        let mut network = FlowNetwork::new();
+        for node in 0..100_000u32 {
+            network.add_edge(&node, &(node + 1), 2);
+        }
+        assert_eq!(2, network.max_flow(&0, &100_000));
+    }
+}
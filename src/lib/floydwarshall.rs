@@ -1,29 +1,42 @@
-use num::{Bounded, Num};
-use std::{collections::HashMap, fmt::Debug, hash::Hash};
+use anyhow::{bail, Result};
+use num::{Bounded, CheckedAdd, Num};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Barrier, RwLock,
+    },
+    thread,
+};
 
-pub fn solve<Id: Clone + Eq + Hash, L: Copy + Bounded + Num + Ord>(
-    all_edges: &HashMap<Id, Vec<(Id, L)>>,
-    routes: &Vec<(Id, Id)>,
-) -> Vec<Option<L>>
+use crate::graph::FiniteGraph;
+
+// Shortest distances between every pair of nodes in the graph. Fails if the graph has a negative
+// cycle, as then there is no shortest route between the nodes on it.
+pub fn distances<G: FiniteGraph>(graph: &G) -> Result<DistanceMatrix<G::Node, G::Cost>>
 where
-    <L as Num>::FromStrRadixErr: Debug,
+    G::Cost: Bounded + CheckedAdd + Num + Ord + Send + Sync,
 {
-    let mut solver: Solver<Id, L> = Solver::from_raw_map(all_edges);
-    solver.solve();
-    routes
-        .into_iter()
-        .map(|(start, end)| solver.matrix[solver.id_to_node[start]][solver.id_to_node[end]])
-        .collect()
+    Solver::from_graph(graph)?.solve()
 }
 
-pub fn solve_uniform_edges<Id: Clone + Eq + Hash, L: Copy + Bounded + Num + Ord>(
-    all_edges: &HashMap<Id, Vec<Id>>,
-    routes: &Vec<(Id, Id)>,
-) -> Vec<Option<L>>
+// As distances, but also able to give the nodes along each route.
+pub fn distances_with_paths<G: FiniteGraph>(graph: &G) -> Result<DistanceMatrix<G::Node, G::Cost>>
 where
-    <L as Num>::FromStrRadixErr: Debug,
+    G::Cost: Bounded + CheckedAdd + Num + Ord + Send + Sync,
 {
-    solve(
+    Solver::from_graph(graph)?.with_paths().solve()
+}
+
+pub fn distances_uniform_edges<
+    Id: Clone + Eq + Hash,
+    L: Copy + Bounded + CheckedAdd + Num + Ord + Send + Sync,
+>(
+    all_edges: &HashMap<Id, Vec<Id>>,
+) -> Result<DistanceMatrix<Id, L>> {
+    distances(
         &all_edges
             .iter()
             .map(|(id, edges)| {
@@ -31,64 +44,502 @@ where
                     id.clone(),
                     edges
                         .iter()
-                        .map(|edge| (edge.clone(), L::from_str_radix("1", 10).unwrap()))
+                        .map(|edge| (edge.clone(), L::one()))
                         .collect::<Vec<_>>(),
                 )
             })
-            .collect(),
-        routes,
+            .collect::<HashMap<_, _>>(),
     )
 }
 
-struct Solver<Id: Clone + Eq + Hash, L: Copy + Bounded + Num + Ord>
+// Distances for just the given routes.
+pub fn solve<G: FiniteGraph>(
+    graph: &G,
+    routes: &[(G::Node, G::Node)],
+) -> Result<Vec<Option<G::Cost>>>
+where
+    G::Cost: Bounded + CheckedAdd + Num + Ord + Send + Sync,
+{
+    let matrix = distances(graph)?;
+    Ok(routes
+        .iter()
+        .map(|(start, end)| matrix.get(start, end))
+        .collect())
+}
+
+// For each route asked for, the nodes along it and its length, if it exists.
+pub type Routes<Id, L> = Vec<Option<(Vec<Id>, L)>>;
+
+// As solve, but also gives the nodes along each route, start and end included.
+pub fn solve_with_paths<G: FiniteGraph>(
+    graph: &G,
+    routes: &[(G::Node, G::Node)],
+) -> Result<Routes<G::Node, G::Cost>>
 where
-    <L as Num>::FromStrRadixErr: Debug,
+    G::Cost: Bounded + CheckedAdd + Num + Ord + Send + Sync,
 {
+    let matrix = distances_with_paths(graph)?;
+    Ok(routes
+        .iter()
+        .map(|(start, end)| Some((matrix.path(start, end)?, matrix.get(start, end)?)))
+        .collect())
+}
+
+pub fn solve_uniform_edges<
+    Id: Clone + Eq + Hash,
+    L: Copy + Bounded + CheckedAdd + Num + Ord + Send + Sync,
+>(
+    all_edges: &HashMap<Id, Vec<Id>>,
+    routes: &[(Id, Id)],
+) -> Result<Vec<Option<L>>> {
+    let matrix = distances_uniform_edges(all_edges)?;
+    Ok(routes
+        .iter()
+        .map(|(start, end)| matrix.get(start, end))
+        .collect())
+}
+
+pub struct Solver<Id: Clone + Eq + Hash, L: Copy + Bounded + CheckedAdd + Num + Ord + Send + Sync> {
     node_count: usize,
-    matrix: Vec<Vec<Option<L>>>,
+    // Row by row in one block, with L::max_value() standing in for no route, which keeps the inner
+    // loop free of Option matching and lets the rows be split between threads. Edges of that length
+    // are rejected, and sums which would reach it are treated as no improvement.
+    matrix: Vec<L>,
+    // next[from * node_count + to] is the first node after from on the shortest route to to. Only
+    // kept if asked for, as it doubles the memory needed.
+    next: Option<Vec<Option<usize>>>,
     id_to_node: HashMap<Id, usize>,
+    node_to_id: Vec<Id>,
+    threads: usize,
 }
 
-impl<Id: Clone + Eq + Hash, L: Copy + Bounded + Num + Ord> Solver<Id, L>
-where
-    <L as Num>::FromStrRadixErr: Debug,
+impl<Id: Clone + Eq + Hash, L: Copy + Bounded + CheckedAdd + Num + Ord + Send + Sync>
+    Solver<Id, L>
 {
-    fn from_raw_map(all_edges: &HashMap<Id, Vec<(Id, L)>>) -> Self {
-        let node_count = all_edges.len();
-        let mut matrix = vec![vec![None; node_count]; node_count];
-        let id_to_node: HashMap<Id, usize> = all_edges
-            .into_iter()
+    pub fn from_graph<G: FiniteGraph<Node = Id, Cost = L>>(graph: &G) -> Result<Self> {
+        let mut node_to_id: Vec<Id> = graph.nodes().collect();
+        let mut id_to_node: HashMap<Id, usize> = node_to_id
+            .iter()
             .enumerate()
-            .map(|(i, (id, _))| (id.clone(), i))
+            .map(|(i, id)| (id.clone(), i))
             .collect();
-        for (node, edges) in all_edges {
-            for (edge_end, length) in edges {
-                matrix[id_to_node[node]][id_to_node[edge_end]] = Some(*length);
+        // Edges can lead to nodes the graph doesn't list, e.g. ones with no edges of their own in
+        // an adjacency map, so number those as they turn up
+        let mut edges = vec![];
+        for i in 0..node_to_id.len() {
+            let node = node_to_id[i].clone();
+            for (edge_end, length) in graph.neighbours(&node) {
+                if length == L::max_value() {
+                    bail!("Edge lengths must be less than the maximum value of their type");
+                }
+                let j = *id_to_node.entry(edge_end).or_insert_with_key(|edge_end| {
+                    node_to_id.push(edge_end.clone());
+                    node_to_id.len() - 1
+                });
+                edges.push((i, j, length));
             }
         }
-        Solver {
+
+        let node_count = node_to_id.len();
+        let mut matrix = vec![L::max_value(); node_count * node_count];
+        for (i, j, length) in edges {
+            let cell = &mut matrix[i * node_count + j];
+            // Keep the shortest of any parallel edges
+            *cell = (*cell).min(length);
+        }
+        Ok(Solver {
             node_count,
             matrix,
+            next: None,
             id_to_node,
-        }
+            node_to_id,
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        })
     }
 
-    fn solve(&mut self) {
-        for intermediate in 0..self.node_count {
-            for from in 0..self.node_count {
-                for to in 0..self.node_count {
-                    self.matrix[from][to] = match (
-                        self.matrix[from][to],
-                        self.matrix[from][intermediate],
-                        self.matrix[intermediate][to],
-                    ) {
-                        (Some(direct), Some(via1), Some(via2)) => Some(direct.min(via1 + via2)),
-                        (None, Some(via1), Some(via2)) => Some(via1 + via2),
-                        (Some(direct), _, _) => Some(direct),
-                        (_, _, _) => None,
-                    }
+    // Track the first step of each route while solving, so that paths can be rebuilt afterwards.
+    pub fn with_paths(mut self) -> Self {
+        let node_count = self.node_count;
+        self.next = Some(
+            self.matrix
+                .iter()
+                .enumerate()
+                .map(|(cell, length)| (*length != L::max_value()).then_some(cell % node_count))
+                .collect(),
+        );
+        self
+    }
+
+    // Defaults to one thread per core.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    pub fn solve(mut self) -> Result<DistanceMatrix<Id, L>> {
+        let node_count = self.node_count;
+        if node_count == 0 {
+            return Ok(self.into_distances());
+        }
+        let rows_per_thread = node_count.div_ceil(self.threads);
+        let chunk_size = rows_per_thread * node_count;
+
+        let row_chunks = self.matrix.chunks_mut(chunk_size);
+        let mut next_chunks: Vec<_> = match &mut self.next {
+            Some(next) => next.chunks_mut(chunk_size).map(Some).collect(),
+            None => vec![],
+        };
+        next_chunks.resize_with(row_chunks.len(), || None);
+
+        let round = Round {
+            node_count,
+            rows_per_thread,
+            via_row: RwLock::new(vec![L::max_value(); node_count]),
+            barrier: Barrier::new(row_chunks.len()),
+            negative_cycle: AtomicBool::new(false),
+        };
+        if row_chunks.len() == 1 {
+            for (rows, next) in row_chunks.zip(next_chunks) {
+                round.work(0, rows, next);
+            }
+        } else {
+            // Each thread keeps the same rows for the whole solve, meeting the others at the
+            // barrier between rounds rather than being started afresh for each one.
+            thread::scope(|scope| {
+                for (chunk, (rows, next)) in row_chunks.zip(next_chunks).enumerate() {
+                    let round = &round;
+                    scope.spawn(move || round.work(chunk, rows, next));
                 }
+            });
+        }
+
+        if round.negative_cycle.into_inner() {
+            bail!("Graph contains a negative cycle");
+        }
+        Ok(self.into_distances())
+    }
+
+    fn into_distances(self) -> DistanceMatrix<Id, L> {
+        DistanceMatrix {
+            node_count: self.node_count,
+            matrix: self.matrix,
+            next: self.next,
+            id_to_node: self.id_to_node,
+            node_to_id: self.node_to_id,
+        }
+    }
+}
+
+// What the threads share while solving: the row for the current intermediate node, and whether
+// any of them has found a negative cycle yet.
+struct Round<L> {
+    node_count: usize,
+    rows_per_thread: usize,
+    via_row: RwLock<Vec<L>>,
+    barrier: Barrier,
+    negative_cycle: AtomicBool,
+}
+
+impl<L: Copy + Bounded + CheckedAdd + Num + Ord> Round<L> {
+    // Every round of the algorithm for one thread's block of rows, starting at row
+    // chunk * rows_per_thread.
+    fn work(&self, chunk: usize, rows: &mut [L], mut next: Option<&mut [Option<usize>]>) {
+        let node_count = self.node_count;
+        let first_row = chunk * self.rows_per_thread;
+        let row_count = rows.len() / node_count;
+
+        for intermediate in 0..node_count {
+            // Routes out of the intermediate node can only change this round if it is on a
+            // negative cycle, which is an error anyway, so every thread works from a copy of them
+            // taken by the thread which owns that row. That leaves every row independent of the
+            // rest.
+            if (first_row..first_row + row_count).contains(&intermediate) {
+                let row_start = (intermediate - first_row) * node_count;
+                self.via_row
+                    .write()
+                    .unwrap()
+                    .copy_from_slice(&rows[row_start..row_start + node_count]);
+            }
+            self.barrier.wait();
+
+            relax_rows(
+                rows,
+                next.as_deref_mut(),
+                intermediate,
+                &self.via_row.read().unwrap(),
+            );
+
+            // Check as we go, since going round a negative cycle again and again can overflow L
+            if (0..row_count).any(|row| rows[row * node_count + first_row + row] < L::zero()) {
+                self.negative_cycle.store(true, Ordering::Relaxed);
             }
+            // Also keeps the next via row from being written while it is still being read
+            self.barrier.wait();
+            if self.negative_cycle.load(Ordering::Relaxed) {
+                return;
+            }
+        }
+    }
+}
+
+// One round of the algorithm for a block of whole rows: shorten any route in them which is
+// improved by going via intermediate, whose own routes onwards are in via_row.
+fn relax_rows<L: Copy + Bounded + CheckedAdd + Num + Ord>(
+    rows: &mut [L],
+    mut next: Option<&mut [Option<usize>]>,
+    intermediate: usize,
+    via_row: &[L],
+) {
+    let node_count = via_row.len();
+    let infinity = L::max_value();
+    for (row_index, row) in rows.chunks_mut(node_count).enumerate() {
+        let via1 = row[intermediate];
+        if via1 == infinity {
+            continue;
         }
+        let row_start = row_index * node_count;
+        let first_step = next.as_ref().map(|next| next[row_start + intermediate]);
+        for (to, (direct, &via2)) in row.iter_mut().zip(via_row).enumerate() {
+            if via2 == infinity {
+                continue;
+            }
+            match via1.checked_add(&via2) {
+                Some(via) if via < *direct => *direct = via,
+                _ => continue,
+            }
+            if let Some(next) = &mut next {
+                next[row_start + to] = first_step.unwrap();
+            }
+        }
+    }
+}
+
+// The solved distances, which can be queried for any pair of nodes. The distance from a node to
+// itself is the length of the shortest cycle through it, or None if there isn't one, rather than 0.
+pub struct DistanceMatrix<Id: Clone + Eq + Hash, L: Copy + Bounded + PartialEq> {
+    node_count: usize,
+    matrix: Vec<L>,
+    next: Option<Vec<Option<usize>>>,
+    id_to_node: HashMap<Id, usize>,
+    node_to_id: Vec<Id>,
+}
+
+impl<Id: Clone + Eq + Hash, L: Copy + Bounded + PartialEq> DistanceMatrix<Id, L> {
+    fn length(&self, start: usize, end: usize) -> Option<L> {
+        let length = self.matrix[start * self.node_count + end];
+        (length != L::max_value()).then_some(length)
+    }
+
+    pub fn get(&self, start: &Id, end: &Id) -> Option<L> {
+        self.length(*self.id_to_node.get(start)?, *self.id_to_node.get(end)?)
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &Id> {
+        self.node_to_id.iter()
+    }
+
+    // Every pair of different nodes with a route between them, and its length.
+    pub fn pairs(&self) -> impl Iterator<Item = (&Id, &Id, L)> {
+        (0..self.node_count).flat_map(move |from| {
+            (0..self.node_count).filter_map(move |to| {
+                (from != to)
+                    .then(|| self.length(from, to))
+                    .flatten()
+                    .map(|length| (&self.node_to_id[from], &self.node_to_id[to], length))
+            })
+        })
+    }
+
+    // Just the distances between the given nodes, e.g. the interesting ones once the routes through
+    // the rest are known. Unknown nodes are ignored. Paths are not kept, as they can pass through
+    // nodes which are dropped.
+    pub fn restrict(&self, nodes: impl IntoIterator<Item = Id>) -> Self {
+        let kept: Vec<(usize, Id)> = nodes
+            .into_iter()
+            .filter_map(|id| Some((*self.id_to_node.get(&id)?, id)))
+            .collect();
+        let matrix = kept
+            .iter()
+            .flat_map(|(from, _)| {
+                kept.iter()
+                    .map(move |(to, _)| self.matrix[from * self.node_count + to])
+            })
+            .collect();
+        let node_to_id: Vec<Id> = kept.into_iter().map(|(_, id)| id).collect();
+        let id_to_node = node_to_id
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (id.clone(), i))
+            .collect();
+        DistanceMatrix {
+            node_count: node_to_id.len(),
+            matrix,
+            next: None,
+            id_to_node,
+            node_to_id,
+        }
+    }
+
+    // The distances as nested maps, from start to end to length, leaving out missing routes and
+    // the distance from a node to itself.
+    pub fn to_map(&self) -> HashMap<Id, HashMap<Id, L>> {
+        let mut map: HashMap<Id, HashMap<Id, L>> = HashMap::with_capacity(self.node_to_id.len());
+        for (start, end, length) in self.pairs() {
+            map.entry(start.clone())
+                .or_default()
+                .insert(end.clone(), length);
+        }
+        map
+    }
+
+    // Nodes along the shortest route from start to end, both included. Panics unless built with
+    // paths.
+    pub fn path(&self, start: &Id, end: &Id) -> Option<Vec<Id>> {
+        let next = self
+            .next
+            .as_ref()
+            .expect("Distances not set up to track paths");
+        let (start, end) = (*self.id_to_node.get(start)?, *self.id_to_node.get(end)?);
+        let mut current = start;
+        let mut path = vec![self.node_to_id[start].clone()];
+        loop {
+            current = next[current * self.node_count + end]?;
+            path.push(self.node_to_id[current].clone());
+            if current == end {
+                return Some(path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths() {
+        let graph: HashMap<_, _> = [
+            ('a', vec![('b', 1), ('c', 5)]),
+            ('b', vec![('c', 1)]),
+            ('c', vec![('a', 1), ('d', 3)]),
+            ('d', vec![]),
+        ]
+        .into_iter()
+        .collect();
+        let routes = [('a', 'd'), ('d', 'a'), ('a', 'a')];
+        assert_eq!(
+            vec![Some(5), None, Some(3)],
+            solve(&graph, &routes).unwrap()
+        );
+        assert_eq!(
+            vec![
+                Some((vec!['a', 'b', 'c', 'd'], 5)),
+                None,
+                Some((vec!['a', 'b', 'c', 'a'], 3))
+            ],
+            solve_with_paths(&graph, &routes).unwrap()
+        );
+    }
+
+    #[test]
+    fn negative_edges() {
+        let graph: HashMap<_, _> = [(1, vec![(2, 4), (3, 1)]), (2, vec![]), (3, vec![(2, -2)])]
+            .into_iter()
+            .collect();
+        assert_eq!(vec![Some(-1)], solve(&graph, &[(1, 2)]).unwrap());
+
+        let graph: HashMap<_, _> = [(1, vec![(2, 1)]), (2, vec![(3, -2)]), (3, vec![(1, 0)])]
+            .into_iter()
+            .collect();
+        assert!(solve(&graph, &[(1, 3)]).is_err());
+    }
+
+    #[test]
+    fn unlisted_nodes() {
+        // 3 only appears as the end of edges
+        let graph: HashMap<_, _> = [(1, vec![(2, 1), (3, 5)]), (2, vec![(3, 1)])]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            vec![Some(2), None],
+            solve(&graph, &[(1, 3), (3, 1)]).unwrap()
+        );
+        assert_eq!(3, distances(&graph).unwrap().nodes().count());
+    }
+
+    #[test]
+    fn long_edges() {
+        // Routes too long to fit in a u8 are as good as missing rather than wrapping round
+        let graph: HashMap<_, _> = [(1, vec![(2, 200u8)]), (2, vec![(3, 200)]), (3, vec![])]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            vec![Some(200), None],
+            solve(&graph, &[(1, 2), (1, 3)]).unwrap()
+        );
+
+        let graph: HashMap<_, _> = [(1, vec![(2, u8::MAX)]), (2, vec![])].into_iter().collect();
+        assert!(solve(&graph, &[(1, 2)]).is_err());
+    }
+
+    #[test]
+    fn threads_agree() {
+        // A ring of 10 with a few shortcuts, in both directions, split between a varying number of
+        // threads
+        let graph: HashMap<_, _> = (0..10)
+            .map(|i| {
+                let mut edges = vec![((i + 1) % 10, 3), ((i + 9) % 10, 2)];
+                if i % 3 == 0 {
+                    edges.push(((i + 5) % 10, 4));
+                }
+                (i, edges)
+            })
+            .collect();
+        let solved = |threads| {
+            let matrix = Solver::from_graph(&graph)
+                .unwrap()
+                .with_paths()
+                .with_threads(threads)
+                .solve()
+                .unwrap();
+            let mut pairs: Vec<_> = matrix
+                .pairs()
+                .map(|(from, to, length)| (*from, *to, length, matrix.path(from, to).unwrap()))
+                .collect();
+            pairs.sort_unstable();
+            pairs
+        };
+        let one_thread = solved(1);
+        assert_eq!(90, one_thread.len());
+        for threads in [2, 3, 4, 16] {
+            assert_eq!(one_thread, solved(threads));
+        }
+    }
+
+    #[test]
+    fn distance_matrix() {
+        let edges = HashMap::from([
+            ("AA", vec!["BB"]),
+            ("BB", vec!["AA", "CC"]),
+            ("CC", vec!["BB", "DD"]),
+            ("DD", vec!["CC"]),
+        ]);
+        let matrix = distances_uniform_edges::<_, i32>(&edges).unwrap();
+        assert_eq!(Some(3), matrix.get(&"AA", &"DD"));
+        assert_eq!(Some(2), matrix.get(&"AA", &"AA"));
+        assert_eq!(None, matrix.get(&"AA", &"EE"));
+        assert_eq!(12, matrix.pairs().count());
+
+        let restricted = matrix.restrict(["AA", "DD", "EE"]);
+        assert_eq!(2, restricted.nodes().count());
+        assert_eq!(Some(3), restricted.get(&"DD", &"AA"));
+        assert_eq!(None, restricted.get(&"AA", &"BB"));
+        assert_eq!(
+            HashMap::from([
+                ("AA", HashMap::from([("DD", 3)])),
+                ("DD", HashMap::from([("AA", 3)]))
+            ]),
+            restricted.to_map()
+        );
     }
 }
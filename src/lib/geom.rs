@@ -0,0 +1,366 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use anyhow::{bail, Error};
+use num::{One, Signed};
+use Direction::*;
+
+use crate::grid::Pos;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+// |a - b| without needing T to be signed
+fn abs_diff<T: Copy + PartialOrd + Sub<Output = T>>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+fn max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+fn min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl<T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T>> Point2<T> {
+    pub fn manhattan(&self, other: &Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    // Distance when diagonal steps are allowed
+    pub fn chebyshev(&self, other: &Self) -> T {
+        max(abs_diff(self.x, other.x), abs_diff(self.y, other.y))
+    }
+
+    pub fn component_min(&self, other: &Self) -> Self {
+        Point2::new(min(self.x, other.x), min(self.y, other.y))
+    }
+
+    pub fn component_max(&self, other: &Self) -> Self {
+        Point2::new(max(self.x, other.x), max(self.y, other.y))
+    }
+}
+
+impl<T: Signed + Copy> Point2<T> {
+    // Each component reduced to -1, 0 or 1, i.e. the single step towards this point from the origin
+    pub fn signum(&self) -> Self {
+        Point2::new(self.x.signum(), self.y.signum())
+    }
+
+    pub fn step(&self, dir: Direction) -> Self {
+        *self + dir.offset()
+    }
+
+    // The 4 orthogonally adjacent points, in Direction::ALL order
+    pub fn neighbours4(&self) -> [Self; 4] {
+        Direction::ALL.map(|dir| self.step(dir))
+    }
+
+    // The 8 adjacent points including diagonals
+    pub fn neighbours8(&self) -> [Self; 8] {
+        let (one, zero) = (T::one(), T::zero());
+        [
+            (zero, -one),
+            (one, -one),
+            (one, zero),
+            (one, one),
+            (zero, one),
+            (-one, one),
+            (-one, zero),
+            (-one, -one),
+        ]
+        .map(|(x, y)| *self + Point2::new(x, y))
+    }
+}
+
+// grid::Pos is (row, col) with row 0 at the top, so the same as (y, x) here
+impl Point2<isize> {
+    pub fn from_pos((row, col): Pos) -> Self {
+        Point2::new(col as isize, row as isize)
+    }
+
+    // None if the point is above or left of the grid
+    pub fn to_pos(self) -> Option<Pos> {
+        Some((self.y.try_into().ok()?, self.x.try_into().ok()?))
+    }
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T>> Point3<T> {
+    pub fn manhattan(&self, other: &Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y) + abs_diff(self.z, other.z)
+    }
+
+    pub fn chebyshev(&self, other: &Self) -> T {
+        max(
+            max(abs_diff(self.x, other.x), abs_diff(self.y, other.y)),
+            abs_diff(self.z, other.z),
+        )
+    }
+
+    pub fn component_min(&self, other: &Self) -> Self {
+        Point3::new(
+            min(self.x, other.x),
+            min(self.y, other.y),
+            min(self.z, other.z),
+        )
+    }
+
+    pub fn component_max(&self, other: &Self) -> Self {
+        Point3::new(
+            max(self.x, other.x),
+            max(self.y, other.y),
+            max(self.z, other.z),
+        )
+    }
+
+    // Whether every component lies within the box with corners min and max (inclusive)
+    pub fn within(&self, min: &Self, max: &Self) -> bool {
+        *self == self.component_max(min) && *self == self.component_min(max)
+    }
+}
+
+impl<T: Copy + One + Add<Output = T> + Sub<Output = T>> Point3<T> {
+    // The 6 points sharing a face with this one
+    pub fn neighbours6(&self) -> [Self; 6] {
+        let one = T::one();
+        let Point3 { x, y, z } = *self;
+        [
+            Point3::new(x - one, y, z),
+            Point3::new(x + one, y, z),
+            Point3::new(x, y - one, z),
+            Point3::new(x, y + one, z),
+            Point3::new(x, y, z - one),
+            Point3::new(x, y, z + one),
+        ]
+    }
+}
+
+macro_rules! impl_ops {
+    ($point:ident { $($field:ident),* }) => {
+        impl<T: Add<Output = T>> Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                $point { $($field: self.$field + rhs.$field),* }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                $point { $($field: self.$field - rhs.$field),* }
+            }
+        }
+
+        impl<T: Copy + Add<Output = T>> AddAssign for $point<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<T: Copy + Sub<Output = T>> SubAssign for $point<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $point { $($field: -self.$field),* }
+            }
+        }
+
+        // Scaling by a constant
+        impl<T: Copy + Mul<Output = T>> Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                $point { $($field: self.$field * rhs),* }
+            }
+        }
+    };
+}
+
+impl_ops!(Point2 { x, y });
+impl_ops!(Point3 { x, y, z });
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(p: Point2<T>) -> Self {
+        (p.x, p.y)
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T> From<Point3<T>> for (T, T, T) {
+    fn from(p: Point3<T>) -> Self {
+        (p.x, p.y, p.z)
+    }
+}
+
+// Compass directions on a grid where y increases downwards, like the rows of puzzle input. Listed
+// clockwise from Right, which is also the order AoC tends to number them in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Right = 0,
+    Down = 1,
+    Left = 2,
+    Up = 3,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Right, Down, Left, Up];
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    // Change in position from taking one step this way
+    pub fn offset<T: Signed>(self) -> Point2<T> {
+        match self {
+            Right => Point2::new(T::one(), T::zero()),
+            Down => Point2::new(T::zero(), T::one()),
+            Left => Point2::new(-T::one(), T::zero()),
+            Up => Point2::new(T::zero(), -T::one()),
+        }
+    }
+}
+
+// Accepts the usual single char forms: U/D/L/R, N/S/W/E or ^/v/</>
+impl TryFrom<char> for Direction {
+    type Error = Error;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        Ok(match c {
+            'R' | 'E' | '>' => Right,
+            'D' | 'S' | 'v' => Down,
+            'L' | 'W' | '<' => Left,
+            'U' | 'N' | '^' => Up,
+            _ => bail!("Not a direction: {:?}", c),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{self, Grid};
+
+    #[test]
+    fn arithmetic() {
+        let a = Point2::new(1, -2);
+        let b = Point2::new(4, 2);
+        assert_eq!(Point2::new(5, 0), a + b);
+        assert_eq!(Point2::new(3, 4), b - a);
+        assert_eq!(Point2::new(-1, 2), -a);
+        assert_eq!(Point2::new(3, -6), a * 3);
+
+        let mut c = Point3::new(1u8, 2, 3);
+        c += Point3::new(1, 1, 1);
+        assert_eq!((2, 3, 4), c.into());
+    }
+
+    #[test]
+    fn distances() {
+        let a = Point2::new(1, -2);
+        let b = Point2::new(4, 2);
+        assert_eq!(7, a.manhattan(&b));
+        assert_eq!(4, a.chebyshev(&b));
+        assert_eq!(3u8, Point3::new(5u8, 1, 1).chebyshev(&Point3::new(2, 2, 2)));
+        assert_eq!(5u8, Point3::new(5u8, 1, 1).manhattan(&Point3::new(2, 2, 2)));
+    }
+
+    #[test]
+    fn turning() {
+        assert_eq!(Up, Right.turn_left());
+        assert_eq!(Right, Up.turn_right());
+        assert_eq!(Left, Right.reverse());
+        for dir in Direction::ALL {
+            assert_eq!(dir, dir.turn_left().turn_left().reverse());
+            assert_eq!(-dir.offset::<i32>(), dir.reverse().offset());
+        }
+        assert_eq!(Point2::new(0, -1), Point2::new(0, 0).step(Up));
+    }
+
+    #[test]
+    fn grid_positions() {
+        let pos = (1, 4);
+        let p = Point2::from_pos(pos);
+        assert_eq!(Point2::new(4, 1), p);
+        assert_eq!(Some(pos), p.to_pos());
+        assert_eq!(None, p.step(Up).step(Up).to_pos());
+
+        // Stepping as a point or as a grid position ends up in the same place
+        let grid = Grid::new(6, 5, ());
+        for dir in Direction::ALL {
+            assert_eq!(p.step(dir).to_pos(), grid.step(pos, grid::offset(dir)));
+        }
+    }
+
+    #[test]
+    fn neighbours() {
+        let p = Point3::new(0i8, 0, 0);
+        assert!(p.neighbours6().iter().all(|n| n.manhattan(&p) == 1));
+        assert!(Point2::new(0, 0)
+            .neighbours8()
+            .iter()
+            .all(|n| n.chebyshev(&Point2::new(0, 0)) == 1));
+        assert!(p.within(&Point3::new(-1, 0, -1), &Point3::new(0, 0, 0)));
+        assert!(!p.within(&Point3::new(1, 0, 0), &Point3::new(2, 0, 0)));
+    }
+}
@@ -0,0 +1,395 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    hash::Hash,
+    marker::PhantomData,
+};
+
+use anyhow::{bail, Result};
+use num::Zero;
+
+// Anything that can say which nodes are reachable from a node, and at what cost. This lets the
+// path-finding solvers work on graphs that are never written out in full, e.g. positions on a grid
+// or states of a simulation, as well as explicit adjacency maps.
+pub trait Graph {
+    type Node: Clone + Eq + Hash;
+    type Cost: Copy;
+
+    fn neighbours(&self, node: &Self::Node) -> impl Iterator<Item = (Self::Node, Self::Cost)>;
+}
+
+// A graph whose nodes can all be listed up front, which all-pairs algorithms need.
+pub trait FiniteGraph: Graph {
+    fn nodes(&self) -> impl Iterator<Item = Self::Node>;
+}
+
+// The adjacency map form used throughout the days: each node with its edges and their lengths.
+impl<T: Clone + Eq + Hash, L: Copy> Graph for HashMap<T, Vec<(T, L)>> {
+    type Node = T;
+    type Cost = L;
+
+    fn neighbours(&self, node: &T) -> impl Iterator<Item = (T, L)> {
+        self.get(node)
+            .into_iter()
+            .flatten()
+            .map(|(edge, length)| (edge.clone(), *length))
+    }
+}
+
+impl<T: Clone + Eq + Hash, L: Copy> FiniteGraph for HashMap<T, Vec<(T, L)>> {
+    fn nodes(&self) -> impl Iterator<Item = T> {
+        self.keys().cloned()
+    }
+}
+
+// An adjacency map where every edge has length 1.
+pub struct UniformEdges<'a, T>(pub &'a HashMap<T, Vec<T>>);
+
+impl<T: Clone + Eq + Hash> Graph for UniformEdges<'_, T> {
+    type Node = T;
+    type Cost = u64;
+
+    fn neighbours(&self, node: &T) -> impl Iterator<Item = (T, u64)> {
+        self.0
+            .get(node)
+            .into_iter()
+            .flatten()
+            .map(|edge| (edge.clone(), 1))
+    }
+}
+
+impl<T: Clone + Eq + Hash> FiniteGraph for UniformEdges<'_, T> {
+    fn nodes(&self) -> impl Iterator<Item = T> {
+        self.0.keys().cloned()
+    }
+}
+
+// A graph defined by a function from a node to its neighbours and the cost of reaching each.
+pub struct FnGraph<N, C, F> {
+    neighbours: F,
+    phantom: PhantomData<fn(&N) -> C>,
+}
+
+pub fn from_fn<N, C, I, F>(neighbours: F) -> FnGraph<N, C, F>
+where
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+{
+    FnGraph {
+        neighbours,
+        phantom: PhantomData,
+    }
+}
+
+impl<N, C, I, F> Graph for FnGraph<N, C, F>
+where
+    N: Clone + Eq + Hash,
+    C: Copy,
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+{
+    type Node = N;
+    type Cost = C;
+
+    fn neighbours(&self, node: &N) -> impl Iterator<Item = (N, C)> {
+        (self.neighbours)(node).into_iter()
+    }
+}
+
+// Every node ordered so that each comes before all the nodes it has edges to, e.g. so that values
+// are worked out before anything which depends on them. Fails if there is a cycle, as then there is
+// no such order.
+pub fn topological_sort<G: FiniteGraph>(graph: &G) -> Result<Vec<G::Node>> {
+    let mut incoming: HashMap<G::Node, usize> = graph.nodes().map(|node| (node, 0)).collect();
+    for node in graph.nodes() {
+        for (edge_end, _) in graph.neighbours(&node) {
+            *incoming.entry(edge_end).or_default() += 1;
+        }
+    }
+
+    // Kahn's algorithm: repeatedly take a node with nothing left pointing at it
+    let mut ready: VecDeque<G::Node> = incoming
+        .iter()
+        .filter(|(_, count)| **count == 0)
+        .map(|(node, _)| node.clone())
+        .collect();
+    let mut order = Vec::with_capacity(incoming.len());
+    while let Some(node) = ready.pop_front() {
+        for (edge_end, _) in graph.neighbours(&node) {
+            let count = incoming.get_mut(&edge_end).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                ready.push_back(edge_end);
+            }
+        }
+        order.push(node);
+    }
+
+    if order.len() < incoming.len() {
+        // Nodes left over are either on a cycle or somewhere after one, so only count the former
+        let on_cycle: usize = strongly_connected_components(graph)
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1
+                    || graph
+                        .neighbours(&component[0])
+                        .any(|(edge_end, _)| edge_end == component[0])
+            })
+            .map(|component| component.len())
+            .sum();
+        bail!(
+            "Graph contains a cycle, through {} of its {} nodes",
+            on_cycle,
+            incoming.len()
+        );
+    }
+    Ok(order)
+}
+
+// Groups of nodes which can all reach each other, using Tarjan's algorithm. Each node is in exactly
+// one group, and the groups come out in reverse topological order: no group has edges to one which
+// comes after it.
+pub fn strongly_connected_components<G: FiniteGraph>(graph: &G) -> Vec<Vec<G::Node>> {
+    // Each node is numbered in the order it's first seen. Its low link is the lowest number it is
+    // known to be able to reach while still on the stack, and a node which can't reach anything
+    // lower than itself is the root of a component.
+    let mut numbers: HashMap<G::Node, usize> = HashMap::new();
+    let mut low_links: HashMap<G::Node, usize> = HashMap::new();
+    let mut stack: Vec<G::Node> = vec![];
+    let mut on_stack: HashSet<G::Node> = HashSet::new();
+    let mut components = vec![];
+
+    for root in graph.nodes() {
+        if numbers.contains_key(&root) {
+            continue;
+        }
+
+        // Done without recursion, so long chains can't overflow the call stack. Each frame is a
+        // node and the neighbours it still has to visit.
+        let mut frames: Vec<(G::Node, Vec<G::Node>)> = vec![];
+        let mut to_visit = Some(root);
+        loop {
+            if let Some(node) = to_visit.take() {
+                let number = numbers.len();
+                numbers.insert(node.clone(), number);
+                low_links.insert(node.clone(), number);
+                stack.push(node.clone());
+                on_stack.insert(node.clone());
+                let mut neighbours: Vec<_> =
+                    graph.neighbours(&node).map(|(next, _)| next).collect();
+                neighbours.reverse();
+                frames.push((node, neighbours));
+            }
+
+            let Some((node, neighbours)) = frames.last_mut() else {
+                break;
+            };
+            if let Some(next) = neighbours.pop() {
+                if !numbers.contains_key(&next) {
+                    to_visit = Some(next);
+                } else if on_stack.contains(&next) {
+                    let low_link = low_links[node].min(numbers[&next]);
+                    low_links.insert(node.clone(), low_link);
+                }
+                continue;
+            }
+
+            // Finished with node, so pass its low link back up and see if it is a root
+            let (node, _) = frames.pop().unwrap();
+            if let Some((parent, _)) = frames.last() {
+                let low_link = low_links[parent].min(low_links[&node]);
+                low_links.insert(parent.clone(), low_link);
+            }
+            if low_links[&node] == numbers[&node] {
+                let mut component = vec![];
+                loop {
+                    let member = stack.pop().unwrap();
+                    on_stack.remove(&member);
+                    let done = member == node;
+                    component.push(member);
+                    if done {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+// An edge as start, end and length.
+pub type Edge<G> = (<G as Graph>::Node, <G as Graph>::Node, <G as Graph>::Cost);
+
+// Kruskal's algorithm, treating every edge as going both ways. Returns the edges picked, and their
+// total length. If the graph isn't connected this gives a tree for each part.
+pub fn minimum_spanning_tree<G: FiniteGraph>(graph: &G) -> (Vec<Edge<G>>, G::Cost)
+where
+    G::Cost: Ord + Zero,
+{
+    let mut edges: Vec<Edge<G>> = vec![];
+    for node in graph.nodes() {
+        for (edge_end, length) in graph.neighbours(&node) {
+            edges.push((node.clone(), edge_end, length));
+        }
+    }
+    edges.sort_by_key(|(_, _, length)| *length);
+
+    let mut trees = DisjointSets::default();
+    let mut total = G::Cost::zero();
+    let picked = edges
+        .into_iter()
+        .filter(|(start, end, _)| trees.union(start, end))
+        .inspect(|(_, _, length)| total = total + *length)
+        .collect();
+    (picked, total)
+}
+
+// Union-find over arbitrary nodes, used to track which tree each node is in so far.
+struct DisjointSets<T> {
+    index: HashMap<T, usize>,
+    parents: Vec<usize>,
+}
+
+impl<T> Default for DisjointSets<T> {
+    fn default() -> Self {
+        DisjointSets {
+            index: HashMap::new(),
+            parents: vec![],
+        }
+    }
+}
+
+impl<T: Clone + Eq + Hash> DisjointSets<T> {
+    fn root(&mut self, node: &T) -> usize {
+        let mut i = *self.index.entry(node.clone()).or_insert_with(|| {
+            self.parents.push(self.parents.len());
+            self.parents.len() - 1
+        });
+        while self.parents[i] != i {
+            // Path halving
+            self.parents[i] = self.parents[self.parents[i]];
+            i = self.parents[i];
+        }
+        i
+    }
+
+    // Join the sets containing a and b, returning false if they were already the same set.
+    fn union(&mut self, a: &T, b: &T) -> bool {
+        let (a, b) = (self.root(a), self.root(b));
+        self.parents[a] = b;
+        a != b
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_graphs() {
+        let weighted = HashMap::from([(1, vec![(2, 5)]), (2, vec![])]);
+        assert_eq!(vec![(2, 5)], weighted.neighbours(&1).collect::<Vec<_>>());
+        assert_eq!(0, weighted.neighbours(&3).count());
+
+        let uniform = HashMap::from([(1, vec![2, 3]), (2, vec![]), (3, vec![])]);
+        let uniform = UniformEdges(&uniform);
+        assert_eq!(
+            vec![(2, 1), (3, 1)],
+            uniform.neighbours(&1).collect::<Vec<_>>()
+        );
+        let mut nodes: Vec<_> = uniform.nodes().collect();
+        nodes.sort_unstable();
+        assert_eq!(vec![1, 2, 3], nodes);
+    }
+
+    #[test]
+    fn fn_graph() {
+        let collatz = from_fn(|n: &u64| {
+            [(
+                if n.is_multiple_of(2) {
+                    n / 2
+                } else {
+                    3 * n + 1
+                },
+                1,
+            )]
+        });
+        assert_eq!(vec![(16, 1)], collatz.neighbours(&5).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn topological_order() {
+        let graph: HashMap<_, _> = [
+            ("root", vec![("pppw", 1), ("sjmn", 1)]),
+            ("pppw", vec![("cczh", 1)]),
+            ("sjmn", vec![("cczh", 1), ("drzm", 1)]),
+            ("cczh", vec![]),
+        ]
+        .into_iter()
+        .collect();
+        let order = topological_sort(&graph).unwrap();
+        assert_eq!(5, order.len());
+        let position = |node| order.iter().position(|n| *n == node).unwrap();
+        for (node, edges) in &graph {
+            for (edge_end, _) in edges {
+                assert!(position(*node) < position(*edge_end));
+            }
+        }
+
+        let cyclic: HashMap<_, _> = [
+            (1, vec![(2, 1)]),
+            (2, vec![(3, 1)]),
+            (3, vec![(2, 1), (4, 1)]),
+            (4, vec![(4, 1), (5, 1)]),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            "Graph contains a cycle, through 3 of its 5 nodes",
+            topological_sort(&cyclic).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn tarjan() {
+        let graph: HashMap<_, _> = [
+            (1, vec![(2, 1)]),
+            (2, vec![(3, 1)]),
+            (3, vec![(1, 1), (4, 1)]),
+            (4, vec![(5, 1)]),
+            (5, vec![(4, 1)]),
+            (6, vec![(5, 1)]),
+        ]
+        .into_iter()
+        .collect();
+        let components: Vec<Vec<_>> = strongly_connected_components(&graph)
+            .into_iter()
+            .map(|mut component| {
+                component.sort_unstable();
+                component
+            })
+            .collect();
+        assert_eq!(3, components.len());
+        for component in [vec![1, 2, 3], vec![4, 5], vec![6]] {
+            assert!(components.contains(&component));
+        }
+        // {4, 5} can't reach the others, so must come first
+        assert_eq!(vec![4, 5], components[0]);
+    }
+
+    #[test]
+    fn kruskal() {
+        let graph: HashMap<_, _> = [
+            ('a', vec![('b', 4), ('c', 1)]),
+            ('b', vec![('c', 2), ('d', 5)]),
+            ('c', vec![('d', 8)]),
+            ('d', vec![]),
+            ('e', vec![('f', 3)]),
+        ]
+        .into_iter()
+        .collect();
+        let (edges, total) = minimum_spanning_tree(&graph);
+        assert_eq!(11, total);
+        assert_eq!(4, edges.len());
+    }
+}
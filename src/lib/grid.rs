@@ -0,0 +1,421 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use anyhow::{bail, Result};
+
+use crate::geom::{Direction, Point2};
+
+// (row, col), with row 0 at the top. As a geom::Point2, row is y and col is x.
+pub type Pos = (usize, usize);
+
+// The orthogonal directions in the order up, down, left, right, which neighbours4 keeps to
+pub const ORTHOGONAL: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+// Row then column offsets of the orthogonal directions followed by the diagonals, clockwise from
+// up-left
+pub const ALL_DIRECTIONS: [(isize, isize); 8] = [
+    (-1, 0),
+    (1, 0),
+    (0, -1),
+    (0, 1),
+    (-1, -1),
+    (-1, 1),
+    (1, 1),
+    (1, -1),
+];
+
+// Row then column offset of one step in dir
+pub fn offset(dir: Direction) -> (isize, isize) {
+    let Point2 { x, y } = dir.offset();
+    (y, x)
+}
+
+// Dense, rectangular grid stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        if let Some(row) = rows.iter().position(|row| row.len() != width) {
+            bail!(
+                "Row {} has length {} but row 0 has length {}",
+                row,
+                rows[row].len(),
+                width
+            );
+        }
+        Ok(Grid {
+            cells: rows.into_iter().flatten().collect(),
+            width,
+            height,
+        })
+    }
+
+    // Build a grid from one line per row, mapping each char to a cell. All lines must be the
+    // same length.
+    pub fn parse(s: &str, f: impl FnMut(char) -> T) -> Result<Self> {
+        let mut f = f;
+        Self::from_rows(
+            s.lines()
+                .map(|line| line.chars().map(&mut f).collect())
+                .collect(),
+        )
+    }
+
+    // As parse, but lines shorter than the longest are filled out with pad.
+    pub fn parse_padded(s: &str, pad: T, f: impl FnMut(char) -> T) -> Self
+    where
+        T: Clone,
+    {
+        let mut f = f;
+        let width = s
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut grid = Grid::new(width, s.lines().count(), pad);
+        for (row, line) in s.lines().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                grid[(row, col)] = f(c);
+            }
+        }
+        grid
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, (row, col): Pos) -> bool {
+        row < self.height && col < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        if self.in_bounds(pos) {
+            Some(&self.cells[pos.0 * self.width + pos.1])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.in_bounds(pos) {
+            Some(&mut self.cells[pos.0 * self.width + pos.1])
+        } else {
+            None
+        }
+    }
+
+    // Every position in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    // Positions of cells matching the predicate, row by row.
+    pub fn find_all<'a>(&'a self, p: impl Fn(&T) -> bool + 'a) -> impl Iterator<Item = Pos> + 'a {
+        self.iter()
+            .filter(move |(_, cell)| p(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.width, "Column {} out of bounds", col);
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    // Move one step by a (row, col) offset, if that stays on the grid.
+    pub fn step(&self, (row, col): Pos, (drow, dcol): (isize, isize)) -> Option<Pos> {
+        let new_pos = (row.checked_add_signed(drow)?, col.checked_add_signed(dcol)?);
+        self.in_bounds(new_pos).then_some(new_pos)
+    }
+
+    // Positions reached by repeatedly stepping by offset from pos (not including pos), stopping at
+    // the edge of the grid.
+    pub fn ray(&self, pos: Pos, offset: (isize, isize)) -> impl Iterator<Item = Pos> + '_ {
+        std::iter::successors(self.step(pos, offset), move |pos| self.step(*pos, offset))
+    }
+
+    // The up to 4 orthogonally adjacent positions, in the order of ORTHOGONAL.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ORTHOGONAL
+            .into_iter()
+            .filter_map(move |dir| self.step(pos, offset(dir)))
+    }
+
+    // The up to 8 adjacent positions including diagonals, in the order of ALL_DIRECTIONS.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ALL_DIRECTIONS
+            .into_iter()
+            .filter_map(move |offset| self.step(pos, offset))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        self.get(pos).unwrap_or_else(|| {
+            panic!(
+                "Position {:?} outside {}x{} grid",
+                pos, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("Position {:?} outside {}x{} grid", pos, width, height))
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Unbounded grid which only stores the cells that have been set, for when the interesting area is
+// small or not known up front. Positions are (row, col) and may be negative.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<(isize, isize), T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, pos: (isize, isize)) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    pub fn get_mut(&mut self, pos: (isize, isize)) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    pub fn insert(&mut self, pos: (isize, isize), cell: T) -> Option<T> {
+        self.cells.insert(pos, cell)
+    }
+
+    pub fn remove(&mut self, pos: (isize, isize)) -> Option<T> {
+        self.cells.remove(&pos)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((isize, isize), &T)> {
+        self.cells.iter().map(|(pos, cell)| (*pos, cell))
+    }
+
+    // Smallest and largest (row, col) of any set cell.
+    pub fn bounds(&self) -> Option<((isize, isize), (isize, isize))> {
+        let mut positions = self.cells.keys();
+        let first = *positions.next()?;
+        Some(positions.fold((first, first), |(min, max), &(row, col)| {
+            (
+                (min.0.min(row), min.1.min(col)),
+                (max.0.max(row), max.1.max(col)),
+            )
+        }))
+    }
+
+    pub fn neighbours4(pos: (isize, isize)) -> impl Iterator<Item = (isize, isize)> {
+        ORTHOGONAL.into_iter().map(move |dir| {
+            let (drow, dcol) = offset(dir);
+            (pos.0 + drow, pos.1 + dcol)
+        })
+    }
+
+    pub fn neighbours8(pos: (isize, isize)) -> impl Iterator<Item = (isize, isize)> {
+        ALL_DIRECTIONS
+            .into_iter()
+            .map(move |(drow, dcol)| (pos.0 + drow, pos.1 + dcol))
+    }
+
+    // Copy the bounding box of the set cells into a dense grid, filling the gaps. Also returns the
+    // sparse position of the dense grid's (0, 0).
+    pub fn to_grid(&self, fill: T) -> (Grid<T>, (isize, isize))
+    where
+        T: Clone,
+    {
+        let Some((min, max)) = self.bounds() else {
+            return (Grid::new(0, 0, fill), (0, 0));
+        };
+        let mut grid = Grid::new(
+            (max.1 - min.1 + 1) as usize,
+            (max.0 - min.0 + 1) as usize,
+            fill,
+        );
+        for (&(row, col), cell) in &self.cells {
+            grid[((row - min.0) as usize, (col - min.1) as usize)] = cell.clone();
+        }
+        (grid, min)
+    }
+}
+
+impl<T> FromIterator<((isize, isize), T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = ((isize, isize), T)>>(iter: I) -> Self {
+        SparseGrid {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> Extend<((isize, isize), T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = ((isize, isize), T)>>(&mut self, iter: I) {
+        self.cells.extend(iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "123\n456";
+
+    fn sample() -> Grid<u32> {
+        Grid::parse(SAMPLE, |c| c.to_digit(10).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let grid = sample();
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(6, grid[(1, 2)]);
+        assert_eq!(None, grid.get((2, 0)));
+        assert_eq!(SAMPLE, grid.to_string());
+        assert!(Grid::parse("12\n3", |c| c).is_err());
+    }
+
+    #[test]
+    fn parse_padded() {
+        let grid = Grid::parse_padded("ab\nc", '.', |c| c);
+        assert_eq!("ab\nc.", grid.to_string());
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = sample();
+        assert_eq!(&[4, 5, 6], grid.row(1));
+        assert_eq!(
+            vec![&[1, 2, 3][..], &[4, 5, 6]],
+            grid.rows().collect::<Vec<_>>()
+        );
+        assert_eq!(vec![&2, &5], grid.column(1).collect::<Vec<_>>());
+        assert_eq!(
+            vec![(0, 2), (1, 1)],
+            grid.find_all(|c| c % 4 == 3 || *c == 5).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = sample();
+        assert_eq!(
+            vec![(1, 0), (0, 1)],
+            grid.neighbours4((0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(0, 1), (1, 0), (1, 2), (0, 0), (0, 2)],
+            grid.neighbours8((1, 1)).collect::<Vec<_>>()
+        );
+        assert_eq!(4, SparseGrid::<()>::neighbours4((0, 0)).count());
+    }
+
+    #[test]
+    fn rays() {
+        let grid = sample();
+        assert_eq!(
+            vec![(1, 1), (1, 0)],
+            grid.ray((1, 2), (0, -1)).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![(0, 1)], grid.ray((1, 0), (-1, 1)).collect::<Vec<_>>());
+        assert_eq!(0, grid.ray((0, 0), (-1, 0)).count());
+    }
+
+    #[test]
+    fn sparse_to_grid() {
+        let mut sparse: SparseGrid<char> = [((-1, 3), 'a'), ((1, 4), 'b')].into_iter().collect();
+        sparse.insert((0, 3), 'c');
+        assert_eq!(Some(((-1, 3), (1, 4))), sparse.bounds());
+
+        let (grid, origin) = sparse.to_grid('.');
+        assert_eq!((-1, 3), origin);
+        assert_eq!("a.\nc.\n.b", grid.to_string());
+    }
+}
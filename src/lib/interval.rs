@@ -1,7 +1,14 @@
+use anyhow::{anyhow, bail, Error};
 use itertools::Itertools;
 use num::PrimInt;
 
-use std::fmt::Debug;
+use std::{
+    cmp::Ordering,
+    fmt::{self, Debug, Display},
+    mem,
+    ops::Sub,
+    str::FromStr,
+};
 
 macro_rules! i {
     ($min:expr, $max:expr) => {
@@ -18,33 +25,69 @@ macro_rules! is {
     };
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct Interval<T: PrimInt + Debug> {
+// The whole numbers from min to max, both included. Ordered by min, then max.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval<T: PrimInt + Debug> {
+    // Kept private so that min <= max always holds
     min: T,
     max: T,
 }
 
 impl<T: PrimInt + Debug> Interval<T> {
-    fn new(min: T, max: T) -> Self {
+    // Panics if min > max, as there are no empty intervals
+    pub fn new(min: T, max: T) -> Self {
         assert!(min <= max);
         Interval { min, max }
     }
 
-    fn union(self, other: Self) -> Option<Self> {
-        let mini = self.min(other);
-        let maxi = self.max(other);
+    // The smallest number in the interval
+    pub fn min(self) -> T {
+        self.min
+    }
+
+    // The largest number in the interval
+    pub fn max(self) -> T {
+        self.max
+    }
+
+    // Whether x is one of the numbers in the interval
+    pub fn contains(self, x: T) -> bool {
+        self.min <= x && x <= self.max
+    }
+
+    // Whether every number in other is also in this interval
+    pub fn contains_all(self, other: Self) -> bool {
+        self.min <= other.min && other.max <= self.max
+    }
+
+    // Whether they share at least one number
+    pub fn overlaps(self, other: Self) -> bool {
+        self.min <= other.max && other.min <= self.max
+    }
+
+    // How many numbers the interval covers. There's no is_empty, as it never is.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(self) -> u64 {
+        (self.max.to_i128().unwrap() - self.min.to_i128().unwrap() + 1) as u64
+    }
+
+    // The single interval covering both, if they overlap or touch
+    pub fn union(self, other: Self) -> Option<Self> {
+        let mini = Ord::min(self, other);
+        let maxi = Ord::max(self, other);
 
         // With integer intervals, there is no gap between neighbouring numbers, so join them to a single interval in a union.
-        if maxi.min <= mini.max + T::one() {
+        if maxi.min <= mini.max.saturating_add(T::one()) {
             Some(i!(mini.min, mini.max.max(maxi.max)))
         } else {
             None
         }
     }
 
-    fn intersection(self, other: Self) -> Option<Self> {
-        let mini = self.min(other);
-        let maxi = self.max(other);
+    // The numbers in both, if there are any
+    pub fn intersection(self, other: Self) -> Option<Self> {
+        let mini = Ord::min(self, other);
+        let maxi = Ord::max(self, other);
 
         if mini.max >= maxi.min {
             Some(i!(maxi.min, mini.max.min(maxi.max)))
@@ -53,8 +96,11 @@ impl<T: PrimInt + Debug> Interval<T> {
         }
     }
 
-    // Returns 0 - 2 intervals
-    fn difference(self, other: Self) -> Vec<Self> {
+    // The numbers in this interval but not other, as 0 - 2 intervals, in order
+    pub fn difference(self, other: Self) -> Vec<Self> {
+        if !self.overlaps(other) {
+            return vec![self];
+        }
         match (self.min < other.min, self.max > other.max) {
             (true, true) => vec![
                 i!(self.min, other.min - T::one()),
@@ -67,11 +113,165 @@ impl<T: PrimInt + Debug> Interval<T> {
     }
 }
 
+// Parses the "2-4" form puzzles tend to use. Either end may be negative, e.g. "-3--1".
+impl<T: PrimInt + Debug + FromStr> FromStr for Interval<T>
+where
+    <T as FromStr>::Err: Display,
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some(split) = s.get(1..).and_then(|rest| rest.find('-')).map(|i| i + 1) else {
+            bail!("Interval {:?} is not in the form min-max", s);
+        };
+        let parse = |end: &str| {
+            end.trim()
+                .parse::<T>()
+                .map_err(|err| anyhow!("Bad interval end {:?} in {:?}: {}", end, s, err))
+        };
+        let (min, max) = (parse(&s[..split])?, parse(&s[split + 1..])?);
+        if min > max {
+            bail!("Interval {:?} ends before it starts", s);
+        }
+        Ok(Interval { min, max })
+    }
+}
+
+impl<T: PrimInt + Debug + Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.min, self.max)
+    }
+}
+
+// A range from start up to but not including end, for endpoints that aren't whole numbers, or
+// where the end being excluded is more natural. start == end is an empty range.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct HalfOpenInterval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Copy + PartialOrd> HalfOpenInterval<T> {
+    // Panics if start > end
+    pub fn new(start: T, end: T) -> Self {
+        assert!(start <= end);
+        HalfOpenInterval { start, end }
+    }
+
+    // Whether the range has no points at all, i.e. start == end
+    pub fn is_empty(self) -> bool {
+        self.start == self.end
+    }
+
+    // Whether x is at or after start, and before end
+    pub fn contains(self, x: T) -> bool {
+        self.start <= x && x < self.end
+    }
+
+    // Whether they share any points, so ranges which only touch don't overlap
+    pub fn overlaps(self, other: Self) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    // The points in both, if there are any
+    pub fn intersection(self, other: Self) -> Option<Self> {
+        self.overlaps(other).then(|| {
+            let start = if self.start > other.start {
+                self.start
+            } else {
+                other.start
+            };
+            let end = if self.end < other.end {
+                self.end
+            } else {
+                other.end
+            };
+            HalfOpenInterval { start, end }
+        })
+    }
+
+    // The single range covering both, if they overlap or touch. An empty range adds nothing, so
+    // joins onto anything.
+    pub fn union(self, other: Self) -> Option<Self> {
+        if self.is_empty() {
+            return Some(other);
+        }
+        if other.is_empty() {
+            return Some(self);
+        }
+        (self.start <= other.end && other.start <= self.end).then(|| {
+            let start = if self.start < other.start {
+                self.start
+            } else {
+                other.start
+            };
+            let end = if self.end > other.end {
+                self.end
+            } else {
+                other.end
+            };
+            HalfOpenInterval { start, end }
+        })
+    }
+}
+
+impl<T: Copy + PartialOrd + Sub<Output = T>> HalfOpenInterval<T> {
+    // The distance from start to end
+    pub fn len(self) -> T {
+        self.end - self.start
+    }
+}
+
+// The same numbers as a half open range, which fails if the interval includes T's largest value,
+// as then the end is out of range.
+impl<T: PrimInt + Debug> TryFrom<Interval<T>> for HalfOpenInterval<T> {
+    type Error = Error;
+
+    fn try_from(interval: Interval<T>) -> Result<Self, Self::Error> {
+        let Some(end) = interval.max.checked_add(&T::one()) else {
+            bail!("Interval {:?} has no end it could stop before", interval);
+        };
+        Ok(HalfOpenInterval::new(interval.min, end))
+    }
+}
+
+// Parses Rust's own "start..end" syntax
+impl<T: Copy + PartialOrd + FromStr> FromStr for HalfOpenInterval<T>
+where
+    <T as FromStr>::Err: Display,
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((start, end)) = s.split_once("..") else {
+            bail!("Interval {:?} is not in the form start..end", s);
+        };
+        let parse = |end: &str| {
+            end.trim()
+                .parse::<T>()
+                .map_err(|err| anyhow!("Bad interval end {:?} in {:?}: {}", end, s, err))
+        };
+        let (start, end) = (parse(start)?, parse(end)?);
+        match start.partial_cmp(&end) {
+            // e.g. NaN, which isn't before or after anything
+            None => bail!("Interval {:?} has ends which can't be compared", s),
+            Some(Ordering::Greater) => bail!("Interval {:?} ends before it starts", s),
+            _ => Ok(HalfOpenInterval { start, end }),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct IntervalSet<T: PrimInt + Debug> {
     intervals: Vec<Interval<T>>,
 }
 
+impl<T: PrimInt + Debug> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T: PrimInt + Debug> IntervalSet<T> {
     pub fn new() -> Self {
         IntervalSet { intervals: vec![] }
@@ -88,86 +288,425 @@ impl<T: PrimInt + Debug> IntervalSet<T> {
     }
 
     pub fn get_intervals(&self) -> Vec<(T, T)> {
+        self.iter().collect()
+    }
+
+    // Each range in the set as (min, max), in order
+    pub fn iter(&self) -> impl Iterator<Item = (T, T)> + '_ {
         self.intervals
             .iter()
             .map(|interval| (interval.min, interval.max))
-            .collect()
     }
 
+    pub fn contains(&self, x: T) -> bool {
+        // The first interval which doesn't end before x is the only one which could hold it
+        let i = self.intervals.partition_point(|interval| interval.max < x);
+        self.intervals
+            .get(i)
+            .is_some_and(|interval| interval.contains(x))
+    }
+
+    // How many numbers the set covers
+    pub fn len(&self) -> u64 {
+        self.intervals.iter().map(|interval| interval.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    // The ranges between those in the set, so not including anything before the first or after
+    // the last
+    pub fn gaps(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.intervals
+            .iter()
+            .tuple_windows()
+            .map(|(a, b)| (a.max + T::one(), b.min - T::one()))
+    }
+
+    // Everything from min to max which isn't in the set
+    pub fn complement_within(&self, min: T, max: T) -> Self {
+        Self::from_interval(min, max).difference(self)
+    }
+
+    // The smallest number from start onwards which isn't in the set, if there is one
+    pub fn first_uncovered(&self, start: T) -> Option<T> {
+        let i = self
+            .intervals
+            .partition_point(|interval| interval.max < start);
+        match self.intervals.get(i) {
+            Some(interval) if interval.min <= start => {
+                (interval.max < T::max_value()).then(|| interval.max + T::one())
+            }
+            _ => Some(start),
+        }
+    }
+
+    // Add the range min to max (inclusive) to the set, in place. Does nothing if min > max.
+    pub fn insert(&mut self, min: T, max: T) {
+        if min > max {
+            return;
+        }
+        // The intervals from start to end overlap or touch the new one, so get merged into it
+        let start = self
+            .intervals
+            .partition_point(|interval| interval.max.saturating_add(T::one()) < min);
+        let end = self
+            .intervals
+            .partition_point(|interval| interval.min <= max.saturating_add(T::one()));
+        let mut merged = i!(min, max);
+        if start < end {
+            merged.min = merged.min.min(self.intervals[start].min);
+            merged.max = merged.max.max(self.intervals[end - 1].max);
+        }
+        self.intervals.splice(start..end, [merged]);
+    }
+
+    // Take the range min to max (inclusive) out of the set, in place. Does nothing if min > max.
+    pub fn remove(&mut self, min: T, max: T) {
+        if min > max {
+            return;
+        }
+        let removed = i!(min, max);
+        let start = self
+            .intervals
+            .partition_point(|interval| interval.max < min);
+        let end = self
+            .intervals
+            .partition_point(|interval| interval.min <= max);
+        let pieces: Vec<_> = self.intervals[start..end]
+            .iter()
+            .flat_map(|interval| interval.difference(removed))
+            .collect();
+        self.intervals.splice(start..end, pieces);
+    }
+
+    // All the operations below rely on the intervals in a set being sorted, with a gap of at least
+    // one number between each, and keep it that way. This lets them walk along both sets at once.
+
     pub fn union(&self, other: &Self) -> Self {
-        let mut intervals = self.intervals.clone();
-        intervals.append(&mut other.intervals.clone());
+        let mut intervals: Vec<Interval<T>> =
+            Vec::with_capacity(self.intervals.len() + other.intervals.len());
 
-        IntervalSet { intervals }.simplified()
+        for &interval in self.intervals.iter().merge(other.intervals.iter()) {
+            // Sorted by min, so an interval can only join onto the last one so far
+            if let Some(last) = intervals.last_mut() {
+                if let Some(union) = last.union(interval) {
+                    *last = union;
+                    continue;
+                }
+            }
+            intervals.push(interval);
+        }
+
+        IntervalSet { intervals }
     }
 
     pub fn intersection(&self, other: &Self) -> Self {
         let mut intervals = vec![];
+        let (mut i, mut j) = (0, 0);
 
-        let mut temp_intervals = self.intervals.clone();
-        temp_intervals.append(&mut other.intervals.clone());
-        temp_intervals.sort();
-
-        for (i1, i2) in temp_intervals.into_iter().tuple_windows() {
-            if let Some(intersection) = i1.intersection(i2) {
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            if let Some(intersection) = a.intersection(b) {
                 intervals.push(intersection);
             }
+            // Whichever ends first can't overlap anything further on in the other set
+            if a.max < b.max {
+                i += 1;
+            } else {
+                j += 1;
+            }
         }
 
-        IntervalSet { intervals }.simplified()
+        IntervalSet { intervals }
     }
 
     pub fn difference(&self, other: &Self) -> Self {
         let mut intervals = vec![];
+        let mut others = other.intervals.iter().peekable();
+
+        for &interval in self.intervals.iter() {
+            // Anything ending before this interval ends before all the later ones too
+            while others.next_if(|other| other.max < interval.min).is_some() {}
 
-        for &self_interval in self.intervals.iter() {
-            let mut current_intervals = vec![self_interval];
-            let mut next_intervals = vec![];
-            for &other_interval in other.intervals.iter() {
-                for current_interval in current_intervals {
-                    next_intervals.append(&mut current_interval.difference(other_interval))
+            // Cut out each overlapping interval in turn, leaving the last one in place as it may
+            // overlap the next interval as well
+            let mut remaining = Some(interval);
+            let mut overlapping = others.clone();
+            while let (Some(current), Some(&&other)) = (remaining, overlapping.peek()) {
+                if other.min > current.max {
+                    break;
                 }
-                current_intervals = next_intervals;
-                next_intervals = vec![];
+                remaining = None;
+                for piece in current.difference(other) {
+                    if piece.min > other.max {
+                        remaining = Some(piece);
+                    } else {
+                        intervals.push(piece);
+                    }
+                }
+                overlapping.next();
             }
-            intervals.append(&mut current_intervals)
+            intervals.extend(remaining);
         }
 
         IntervalSet { intervals }
     }
+}
 
-    fn simplified(&self) -> Self {
-        let mut intervals = vec![];
+// An axis-aligned box is an interval along each axis.
+type Cuboid<T, const N: usize> = [Interval<T>; N];
+
+fn cuboid_intersection<T: PrimInt + Debug, const N: usize>(
+    a: &Cuboid<T, N>,
+    b: &Cuboid<T, N>,
+) -> Option<Cuboid<T, N>> {
+    let mut intersection = *a;
+    for axis in 0..N {
+        intersection[axis] = a[axis].intersection(b[axis])?;
+    }
+    Some(intersection)
+}
+
+// Splits what's left of a after taking out b into at most 2 * N boxes, slicing off the parts
+// outside b one axis at a time.
+fn cuboid_difference<T: PrimInt + Debug, const N: usize>(
+    a: &Cuboid<T, N>,
+    b: &Cuboid<T, N>,
+) -> Vec<Cuboid<T, N>> {
+    let Some(overlap) = cuboid_intersection(a, b) else {
+        return vec![*a];
+    };
+    let mut pieces = vec![];
+    let mut rest = *a;
+    for axis in 0..N {
+        for piece in rest[axis].difference(overlap[axis]) {
+            let mut sliced = rest;
+            sliced[axis] = piece;
+            pieces.push(sliced);
+        }
+        rest[axis] = overlap[axis];
+    }
+    pieces
+}
+
+// A set of points in N dimensions, stored as non-overlapping axis-aligned boxes. Unlike
+// IntervalSet, touching boxes aren't merged, so two equal sets may be made of different boxes.
+#[derive(Clone, Debug)]
+pub struct BoxSet<T: PrimInt + Debug, const N: usize> {
+    boxes: Vec<Cuboid<T, N>>,
+}
+
+impl<T: PrimInt + Debug, const N: usize> Default for BoxSet<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PrimInt + Debug, const N: usize> BoxSet<T, N> {
+    pub fn new() -> Self {
+        BoxSet { boxes: vec![] }
+    }
+
+    // The box with corners min and max (inclusive), or an empty set if min is above max along any
+    // axis
+    pub fn from_box(min: [T; N], max: [T; N]) -> Self {
+        if (0..N).any(|axis| min[axis] > max[axis]) {
+            return Self::new();
+        }
+        BoxSet {
+            boxes: vec![std::array::from_fn(|axis| i!(min[axis], max[axis]))],
+        }
+    }
+
+    // Each box in the set as (min corner, max corner)
+    pub fn boxes(&self) -> impl Iterator<Item = ([T; N], [T; N])> + '_ {
+        self.boxes.iter().map(|cuboid| {
+            (
+                cuboid.map(|interval| interval.min),
+                cuboid.map(|interval| interval.max),
+            )
+        })
+    }
+
+    pub fn contains(&self, point: [T; N]) -> bool {
+        self.boxes
+            .iter()
+            .any(|cuboid| (0..N).all(|axis| cuboid[axis].contains(point[axis])))
+    }
+
+    // How many points the set covers
+    pub fn volume(&self) -> u64 {
+        self.boxes
+            .iter()
+            .map(|cuboid| {
+                cuboid
+                    .iter()
+                    .map(|interval| interval.len())
+                    .product::<u64>()
+            })
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        // Only the parts of other not already covered need adding
+        let mut boxes = self.boxes.clone();
+        boxes.extend(other.difference(self).boxes);
+        BoxSet { boxes }
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let boxes = self
+            .boxes
+            .iter()
+            .cartesian_product(other.boxes.iter())
+            .filter_map(|(a, b)| cuboid_intersection(a, b))
+            .collect();
+        BoxSet { boxes }
+    }
 
-        if self.intervals.is_empty() {
-            return self.clone();
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut boxes = self.boxes.clone();
+        for removed in other.boxes.iter() {
+            boxes = boxes
+                .iter()
+                .flat_map(|cuboid| cuboid_difference(cuboid, removed))
+                .collect();
         }
+        BoxSet { boxes }
+    }
+}
 
-        let mut temp_intervals = self.intervals.clone();
-        temp_intervals.sort();
+// Values attached to ranges, which may overlap, for finding which ranges cover a point. Entries
+// are kept sorted by min, along with the largest max of any entry up to each one, so a search can
+// stop as soon as nothing further back reaches far enough.
+#[derive(Clone, Debug)]
+pub struct IntervalMap<T: PrimInt + Debug, V> {
+    entries: Vec<(Interval<T>, V)>,
+    max_reach: Vec<T>,
+}
 
-        let mut interval_iter = temp_intervals.iter();
+impl<T: PrimInt + Debug, V> Default for IntervalMap<T, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        let mut current_interval = *interval_iter.next().unwrap();
+impl<T: PrimInt + Debug, V> IntervalMap<T, V> {
+    pub fn new() -> Self {
+        IntervalMap {
+            entries: vec![],
+            max_reach: vec![],
+        }
+    }
 
-        for &interval in interval_iter {
-            if let Some(union) = current_interval.union(interval) {
-                current_interval = union;
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Each range as (min, max) with its value, in order of min
+    pub fn iter(&self) -> impl Iterator<Item = ((T, T), &V)> {
+        self.entries
+            .iter()
+            .map(|(interval, value)| ((interval.min, interval.max), value))
+    }
+
+    // Add a value for min to max (inclusive), alongside any already there. Panics if min > max.
+    pub fn insert(&mut self, min: T, max: T, value: V) {
+        let i = self
+            .entries
+            .partition_point(|(interval, _)| interval.min <= min);
+        self.entries.insert(i, (i!(min, max), value));
+        self.update_reach(i);
+    }
+
+    // Add a value for min to max, first merging it with everything it overlaps. The merged entry
+    // covers all their ranges, with a value made by folding merge over the old values in order
+    // and then the new one.
+    pub fn insert_merging<F>(&mut self, min: T, max: T, value: V, mut merge: F)
+    where
+        F: FnMut(V, V) -> V,
+    {
+        // Widening the range can bring in entries passed over earlier in the scan, so keep going
+        // until a pass finds nothing new
+        let mut merged = i!(min, max);
+        let mut taken = vec![false; self.entries.len()];
+        let mut grown = true;
+        while grown {
+            grown = false;
+            for ((interval, _), taken) in self.entries.iter().zip(&mut taken) {
+                if !*taken && interval.intersection(merged).is_some() {
+                    *taken = true;
+                    merged = i!(merged.min.min(interval.min), merged.max.max(interval.max));
+                    grown = true;
+                }
+            }
+        }
+
+        let mut values = vec![];
+        for (entry, taken) in mem::take(&mut self.entries).into_iter().zip(taken) {
+            if taken {
+                values.push(entry.1);
             } else {
-                intervals.push(current_interval);
-                current_interval = interval;
+                self.entries.push(entry);
             }
         }
+        let value = values
+            .into_iter()
+            .chain([value])
+            .reduce(&mut merge)
+            .unwrap();
+        self.update_reach(0);
+        self.insert(merged.min, merged.max, value);
+    }
 
-        intervals.push(current_interval);
+    // Values whose range covers x
+    pub fn stab(&self, x: T) -> Vec<((T, T), &V)> {
+        self.overlapping(x, x)
+    }
 
-        IntervalSet { intervals }
+    // Values whose range shares at least one number with min to max, in order of min
+    pub fn overlapping(&self, min: T, max: T) -> Vec<((T, T), &V)> {
+        let end = self
+            .entries
+            .partition_point(|(interval, _)| interval.min <= max);
+        let mut found: Vec<_> = (0..end)
+            .rev()
+            .take_while(|i| self.max_reach[*i] >= min)
+            .map(|i| &self.entries[i])
+            .filter(|(interval, _)| interval.max >= min)
+            .map(|(interval, value)| ((interval.min, interval.max), value))
+            .collect();
+        found.reverse();
+        found
+    }
+
+    // Redo the running max from entry i onwards
+    fn update_reach(&mut self, i: usize) {
+        self.max_reach.truncate(i);
+        let mut reach = self.max_reach.last().copied();
+        for (interval, _) in &self.entries[i..] {
+            let next = reach.map_or(interval.max, |reach| reach.max(interval.max));
+            self.max_reach.push(next);
+            reach = Some(next);
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
     use test;
 
     #[test]
@@ -200,6 +739,35 @@ mod tests {
         assert_eq!(is!(0, 12), i2.union(&i3).union(&i0.union(&i1)).union(&i4))
     }
 
+    #[test]
+    fn type_bounds() {
+        // Nothing can come after u8::MAX, so nothing should try to add one to it
+        let top = i!(250u8, u8::MAX);
+        assert_eq!(Some(i!(0, u8::MAX)), top.union(i!(0, 249)));
+        assert_eq!(Some(top), top.union(top));
+        assert_eq!(None, top.union(i!(0, 10)));
+
+        let mut set = IntervalSet::from_interval(0u8, 10);
+        set.insert(250, u8::MAX);
+        assert_eq!(
+            vec![(0, 10), (250, u8::MAX)],
+            set.union(&IntervalSet::from_interval(255, 255))
+                .get_intervals()
+        );
+        assert_eq!(
+            vec![(11, 249)],
+            set.complement_within(0, u8::MAX).get_intervals()
+        );
+        assert_eq!(
+            vec![(0, u8::MAX)],
+            set.union(&IntervalSet::from_interval(11, 249))
+                .get_intervals()
+        );
+        assert!(IntervalSet::from_interval(i8::MIN, i8::MAX)
+            .complement_within(i8::MIN, i8::MAX)
+            .is_empty());
+    }
+
     #[test]
     fn intersection() {
         let i0 = is!(0, 3);
@@ -208,6 +776,16 @@ mod tests {
 
         assert_eq!(is!(2, 3), i0.intersection(&i1));
         assert_eq!(is!(0, 3), i0.intersection(&i2));
+
+        // Overlaps which aren't next to each other once sorted
+        let i3 = is!(0, 10).union(&is!(20, 30));
+        let i4 = is!(5, 6).union(&is!(8, 22));
+        assert_eq!(
+            IntervalSet {
+                intervals: vec![i!(5, 6), i!(8, 10), i!(20, 22)]
+            },
+            i3.intersection(&i4)
+        );
     }
 
     #[test]
@@ -220,4 +798,266 @@ mod tests {
         assert_eq!(IntervalSet::new(), i0.difference(&i2));
         assert_eq!(is!(3, 3), i2.difference(&i0.union(&i1)));
     }
+
+    // Property tests against the simplest possible model: the set of numbers covered.
+
+    fn model() -> impl Strategy<Value = HashSet<i32>> {
+        prop::collection::vec((-20..20, 0..8), 0..6).prop_map(|ranges| {
+            ranges
+                .into_iter()
+                .flat_map(|(min, len)| min..=min + len)
+                .collect()
+        })
+    }
+
+    fn from_model(model: &HashSet<i32>) -> IntervalSet<i32> {
+        let mut numbers: Vec<_> = model.iter().copied().collect();
+        numbers.sort_unstable();
+        let mut intervals: Vec<Interval<i32>> = vec![];
+        for number in numbers {
+            match intervals.last_mut() {
+                Some(last) if last.max + 1 == number => last.max = number,
+                _ => intervals.push(i!(number, number)),
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    fn to_model(set: &IntervalSet<i32>) -> HashSet<i32> {
+        set.get_intervals()
+            .into_iter()
+            .flat_map(|(min, max)| min..=max)
+            .collect()
+    }
+
+    // Sorted, with gaps between, so that equal sets always compare equal
+    fn is_normalised(set: &IntervalSet<i32>) -> bool {
+        set.intervals
+            .iter()
+            .tuple_windows()
+            .all(|(a, b)| a.max + 1 < b.min)
+    }
+
+    fn box_model() -> impl Strategy<Value = (BoxSet<i32, 2>, HashSet<[i32; 2]>)> {
+        prop::collection::vec((-6..6, -6..6, 0..5, 0..5), 0..4).prop_map(|boxes| {
+            let mut set = BoxSet::new();
+            let mut model = HashSet::new();
+            for (x, y, width, height) in boxes {
+                set = set.union(&BoxSet::from_box([x, y], [x + width, y + height]));
+                model.extend(
+                    (x..=x + width)
+                        .cartesian_product(y..=y + height)
+                        .map(|(x, y)| [x, y]),
+                );
+            }
+            (set, model)
+        })
+    }
+
+    fn box_to_model(set: &BoxSet<i32, 2>) -> HashSet<[i32; 2]> {
+        let points: Vec<_> = set
+            .boxes()
+            .flat_map(|(min, max)| {
+                (min[0]..=max[0])
+                    .cartesian_product(min[1]..=max[1])
+                    .map(|(x, y)| [x, y])
+            })
+            .collect();
+        let unique: HashSet<_> = points.iter().copied().collect();
+        // The boxes shouldn't overlap
+        assert_eq!(points.len(), unique.len());
+        unique
+    }
+
+    #[test]
+    fn boxes() {
+        let cube = BoxSet::from_box([0, 0, 0], [2, 2, 2]);
+        let centre = BoxSet::from_box([1, 1, 1], [1, 1, 1]);
+        let hollow = cube.difference(&centre);
+        assert_eq!(26, hollow.volume());
+        assert!(!hollow.contains([1, 1, 1]));
+        assert!(hollow.contains([0, 1, 2]));
+        assert_eq!(27, hollow.union(&cube).volume());
+        assert_eq!(1, cube.intersection(&centre).volume());
+        assert!(BoxSet::from_box([0, 3, 0], [2, 2, 2]).is_empty());
+    }
+
+    #[test]
+    fn public_intervals() {
+        let a: Interval<i32> = "2-8".parse().unwrap();
+        let b: Interval<i32> = "-3--1".parse().unwrap();
+        assert_eq!(i!(2, 8), a);
+        assert_eq!(i!(-3, -1), b);
+        assert_eq!((2, 8), (a.min(), a.max()));
+        assert!("8-2".parse::<Interval<i32>>().is_err());
+        assert!("2".parse::<Interval<i32>>().is_err());
+        assert_eq!("-3--1", b.to_string());
+
+        assert!(a.contains(8) && !a.contains(9));
+        assert!(a.contains_all(i!(3, 8)) && !a.contains_all(i!(1, 3)));
+        assert!(a.overlaps(i!(8, 10)) && !a.overlaps(b));
+        assert_eq!(7, a.len());
+        assert_eq!(vec![b], b.difference(a));
+
+        let c: HalfOpenInterval<f64> = "0.5..2".parse().unwrap();
+        assert_eq!(1.5, c.len());
+        assert!(c.contains(0.5) && !c.contains(2.0));
+        assert!(!c.overlaps(HalfOpenInterval::new(2.0, 3.0)));
+        assert_eq!(
+            Some(HalfOpenInterval::new(0.5, 3.0)),
+            c.union(HalfOpenInterval::new(2.0, 3.0))
+        );
+        assert_eq!(
+            Some(HalfOpenInterval::new(1.0, 2.0)),
+            c.intersection(HalfOpenInterval::new(1.0, 5.0))
+        );
+        assert!("NaN..2".parse::<HalfOpenInterval<f64>>().is_err());
+        assert!("0..NaN".parse::<HalfOpenInterval<f64>>().is_err());
+
+        // Empty ranges join onto anything, wherever they are
+        let empty = HalfOpenInterval::new(5, 5);
+        let d = HalfOpenInterval::new(0, 1);
+        assert_eq!(Some(d), empty.union(d));
+        assert_eq!(Some(d), d.union(empty));
+        assert_eq!(
+            HalfOpenInterval::new(2, 9),
+            HalfOpenInterval::try_from(a).unwrap()
+        );
+        assert!(HalfOpenInterval::try_from(Interval::new(3u8, u8::MAX)).is_err());
+        assert!(HalfOpenInterval::new(1, 1).is_empty());
+    }
+
+    #[test]
+    fn interval_map() {
+        let mut map = IntervalMap::new();
+        map.insert(2, 4, 'a');
+        map.insert(6, 8, 'b');
+        map.insert(2, 8, 'c');
+        map.insert(0, 10, 'd');
+        map.insert(5, 5, 'e');
+
+        let values = |found: Vec<((i32, i32), &char)>| -> Vec<char> {
+            found.into_iter().map(|(_, value)| *value).collect()
+        };
+        assert_eq!(vec!['d', 'a', 'c'], values(map.stab(3)));
+        assert_eq!(vec!['d', 'c', 'e'], values(map.stab(5)));
+        assert_eq!(Vec::<char>::new(), values(map.stab(11)));
+        assert_eq!(vec!['d', 'c', 'e', 'b'], values(map.overlapping(5, 6)));
+        assert_eq!(5, map.len());
+
+        let mut counts = IntervalMap::new();
+        counts.insert_merging(0, 2, 1, |a, b| a + b);
+        counts.insert_merging(5, 6, 1, |a, b| a + b);
+        counts.insert_merging(2, 5, 1, |a, b| a + b);
+        counts.insert_merging(8, 9, 1, |a, b| a + b);
+        assert_eq!(
+            vec![((0, 6), &3), ((8, 9), &1)],
+            counts.iter().collect::<Vec<_>>()
+        );
+
+        // Merging with a later entry can widen the range back over an earlier one, which then has
+        // to be merged too, with the values still folded in order of min
+        let mut digits = IntervalMap::new();
+        digits.insert(0, 4, 1);
+        digits.insert(3, 10, 2);
+        digits.insert(12, 14, 5);
+        digits.insert_merging(6, 6, 3, |a, b| a * 10 + b);
+        assert_eq!(
+            vec![((0, 10), &123), ((12, 14), &5)],
+            digits.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(vec![((12, 14), &5)], digits.stab(13));
+    }
+
+    proptest! {
+        #[test]
+        fn interval_map_matches_model(
+            ranges in prop::collection::vec((-20..20, 0..8), 0..8),
+            x in -25..30,
+            len in 0..5,
+        ) {
+            let mut map = IntervalMap::new();
+            for (i, (min, range_len)) in ranges.iter().enumerate() {
+                map.insert(*min, min + range_len, i);
+            }
+            let mut expected: Vec<_> = ranges
+                .iter()
+                .enumerate()
+                .filter(|(_, (min, range_len))| *min <= x + len && min + range_len >= x)
+                .map(|(i, _)| i)
+                .collect();
+            let mut found: Vec<_> = map.overlapping(x, x + len).into_iter().map(|(_, i)| *i).collect();
+            expected.sort_unstable();
+            found.sort_unstable();
+            prop_assert_eq!(expected, found);
+        }
+
+        #[test]
+        fn box_operations_match_model((a, a_model) in box_model(), (b, b_model) in box_model()) {
+            let union = a.union(&b);
+            prop_assert_eq!(&a_model | &b_model, box_to_model(&union));
+            prop_assert_eq!(union.volume(), (&a_model | &b_model).len() as u64);
+            prop_assert_eq!(&a_model & &b_model, box_to_model(&a.intersection(&b)));
+            prop_assert_eq!(&a_model - &b_model, box_to_model(&a.difference(&b)));
+            prop_assert_eq!(a_model.contains(&[0, 0]), a.contains([0, 0]));
+        }
+
+        #[test]
+        fn union_matches_model(a in model(), b in model()) {
+            let union = from_model(&a).union(&from_model(&b));
+            prop_assert!(is_normalised(&union));
+            prop_assert_eq!(&a | &b, to_model(&union));
+        }
+
+        #[test]
+        fn intersection_matches_model(a in model(), b in model()) {
+            let intersection = from_model(&a).intersection(&from_model(&b));
+            prop_assert!(is_normalised(&intersection));
+            prop_assert_eq!(&a & &b, to_model(&intersection));
+        }
+
+        #[test]
+        fn queries_match_model(a in model(), x in -25..35) {
+            let set = from_model(&a);
+            prop_assert_eq!(a.contains(&x), set.contains(x));
+            prop_assert_eq!(a.len() as u64, set.len());
+            prop_assert_eq!((x..).find(|x| !a.contains(x)), set.first_uncovered(x));
+
+            let complement = set.complement_within(-10, 10);
+            prop_assert!(is_normalised(&complement));
+            prop_assert_eq!(
+                (-10..=10).filter(|x| !a.contains(x)).collect::<HashSet<_>>(),
+                to_model(&complement)
+            );
+
+            if let (Some(min), Some(max)) = (a.iter().min(), a.iter().max()) {
+                prop_assert_eq!(
+                    (*min..=*max).filter(|x| !a.contains(x)).collect::<HashSet<_>>(),
+                    set.gaps().flat_map(|(min, max)| min..=max).collect::<HashSet<_>>()
+                );
+            }
+        }
+
+        #[test]
+        fn insert_and_remove_match_model(a in model(), min in -25..25, len in -1..10) {
+            let range: HashSet<_> = (min..=min + len).collect();
+
+            let mut inserted = from_model(&a);
+            inserted.insert(min, min + len);
+            prop_assert!(is_normalised(&inserted));
+            prop_assert_eq!(&a | &range, to_model(&inserted));
+
+            let mut removed = from_model(&a);
+            removed.remove(min, min + len);
+            prop_assert!(is_normalised(&removed));
+            prop_assert_eq!(&a - &range, to_model(&removed));
+        }
+
+        #[test]
+        fn difference_matches_model(a in model(), b in model()) {
+            let difference = from_model(&a).difference(&from_model(&b));
+            prop_assert!(is_normalised(&difference));
+            prop_assert_eq!(&a - &b, to_model(&difference));
+        }
+    }
 }
@@ -1,6 +1,20 @@
+#![feature(iter_intersperse)]
+#![feature(test)]
+
+extern crate test;
+
+pub mod astar;
+pub mod bench;
+pub mod bfs;
 pub mod common_startup;
+pub mod days;
 pub mod dijkstra;
+pub mod expr;
+pub mod flow;
 pub mod floydwarshall;
+pub mod geom;
+pub mod graph;
+pub mod grid;
 pub mod interval;
 pub mod logger;
 pub mod op_wrapper;
@@ -13,13 +27,17 @@ impl LetterSet {
     pub fn len(&self) -> usize {
         self.backing_store.count_ones() as usize
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.backing_store == 0
+    }
 }
 
 impl From<&[char]> for LetterSet {
     fn from(input: &[char]) -> Self {
         let backing_store = input
             .iter()
-            .fold(0, |acc, c| acc | (1 << (*c as u8 - 'a' as u8)));
+            .fold(0, |acc, c| acc | (1 << (*c as u8 - b'a')));
         LetterSet { backing_store }
     }
 }
@@ -29,7 +47,7 @@ impl FromIterator<char> for LetterSet {
         LetterSet {
             backing_store: iter
                 .into_iter()
-                .fold(0, |acc, c| acc | (1 << (c as u8 - 'a' as u8))),
+                .fold(0, |acc, c| acc | (1 << (c as u8 - b'a'))),
         }
     }
 }
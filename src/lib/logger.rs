@@ -8,13 +8,15 @@ impl log::Log for Logger {
         true
     }
 
+    // Only info goes to stdout, so that diagnostics don't get mixed into output meant for other
+    // programs, like bench --json.
     fn log(&self, record: &log::Record) {
         match record.metadata().level() {
-            Level::Error => println!("{}", format!("{}", record.args()).red()),
-            Level::Warn => println!("{}", format!("{}", record.args()).yellow()),
-            Level::Info => println!("{}", format!("{}", record.args())),
-            Level::Debug => println!("{}", format!("{}", record.args()).blue()),
-            Level::Trace => println!("{}", format!("{}", record.args()).cyan()),
+            Level::Error => eprintln!("{}", format!("{}", record.args()).red()),
+            Level::Warn => eprintln!("{}", format!("{}", record.args()).yellow()),
+            Level::Info => println!("{}", record.args()),
+            Level::Debug => eprintln!("{}", format!("{}", record.args()).blue()),
+            Level::Trace => eprintln!("{}", format!("{}", record.args()).cyan()),
         }
     }
 
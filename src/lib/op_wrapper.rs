@@ -1,23 +1,150 @@
-use num::Num;
+use anyhow::{bail, Context, Result};
+use num::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Num};
 use std::{error::Error, fmt::Display, str::FromStr};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Op {
     Add,
     Sub,
     Mul,
     Div,
+    Rem,
+    Pow,
+    Min,
+    Max,
+    // Comparisons give 1 for true and 0 for false
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
 }
 
 impl Op {
-    pub fn call<T: Num>(&self, left: T, right: T) -> T {
+    // Panics on a negative or fractional exponent, and on anything the underlying operators panic
+    // on.
+    pub fn call<T: Num + PartialOrd + Clone>(&self, left: T, right: T) -> T {
         match self {
             Self::Add => left + right,
             Self::Sub => left - right,
             Self::Mul => left * right,
             Self::Div => left / right,
+            Self::Rem => left % right,
+            Self::Pow => {
+                assert!(
+                    is_exponent(&right),
+                    "Exponent must be a whole number, at least 0"
+                );
+                power(left, right, |a, b| Some(a * b)).unwrap()
+            }
+            Self::Min => {
+                if right < left {
+                    right
+                } else {
+                    left
+                }
+            }
+            Self::Max => {
+                if right > left {
+                    right
+                } else {
+                    left
+                }
+            }
+            Self::Equal => truth(left == right),
+            Self::NotEqual => truth(left != right),
+            Self::Less => truth(left < right),
+            Self::LessEqual => truth(left <= right),
+            Self::Greater => truth(left > right),
+            Self::GreaterEqual => truth(left >= right),
         }
     }
+
+    // As call, but fails instead of panicking or wrapping on overflow, division by zero, or a bad
+    // exponent.
+    pub fn checked_call<T>(&self, left: T, right: T) -> Result<T>
+    where
+        T: Num + PartialOrd + Clone + Display + CheckedAdd + CheckedSub + CheckedMul + CheckedDiv,
+    {
+        let result = match self {
+            Self::Add => left.checked_add(&right),
+            Self::Sub => left.checked_sub(&right),
+            Self::Mul => left.checked_mul(&right),
+            Self::Div | Self::Rem if right.is_zero() => {
+                bail!("Division by zero: {} {} {}", left, self, right)
+            }
+            Self::Div => left.checked_div(&right),
+            // The remainder can only overflow when the division does
+            Self::Rem => left
+                .checked_div(&right)
+                .map(|_| left.clone() % right.clone()),
+            Self::Pow if !is_exponent(&right) => bail!(
+                "Exponent must be a whole number, at least 0: {} ^ {}",
+                left,
+                right
+            ),
+            Self::Pow => power(left.clone(), right.clone(), |a, b| a.checked_mul(&b)),
+            _ => Some(self.call(left.clone(), right.clone())),
+        };
+        result.with_context(|| format!("Overflow: {} {} {}", left, self, right))
+    }
+
+    // How tightly the op binds when written between its operands, higher binding tighter. None for
+    // ops written as calls, like min(a, b).
+    pub fn precedence(&self) -> Option<u8> {
+        match self {
+            Self::Equal
+            | Self::NotEqual
+            | Self::Less
+            | Self::LessEqual
+            | Self::Greater
+            | Self::GreaterEqual => Some(1),
+            Self::Add | Self::Sub => Some(2),
+            Self::Mul | Self::Div | Self::Rem => Some(3),
+            Self::Pow => Some(4),
+            Self::Min | Self::Max => None,
+        }
+    }
+
+    // Whether a op b op c means a op (b op c), rather than (a op b) op c.
+    pub fn is_right_associative(&self) -> bool {
+        matches!(self, Self::Pow)
+    }
+}
+
+fn truth<T: Num>(value: bool) -> T {
+    if value {
+        T::one()
+    } else {
+        T::zero()
+    }
+}
+
+fn is_exponent<T: Num + PartialOrd + Clone>(value: &T) -> bool {
+    *value >= T::zero() && (value.clone() % T::one()).is_zero()
+}
+
+// Raise base to a whole exponent by repeated squaring, multiplying with mul, which can fail.
+fn power<T: Num + Clone>(
+    mut base: T,
+    mut exponent: T,
+    mul: impl Fn(T, T) -> Option<T>,
+) -> Option<T> {
+    let two = T::one() + T::one();
+    let mut result = T::one();
+    while !exponent.is_zero() {
+        if !(exponent.clone() % two.clone()).is_zero() {
+            result = mul(result, base.clone())?;
+            exponent = exponent - T::one();
+        }
+        exponent = exponent / two.clone();
+        // Don't square once it's no longer needed, in case that overflows
+        if !exponent.is_zero() {
+            base = mul(base.clone(), base)?;
+        }
+    }
+    Some(result)
 }
 
 impl FromStr for Op {
@@ -29,20 +156,39 @@ impl FromStr for Op {
             "-" => Ok(Self::Sub),
             "*" => Ok(Self::Mul),
             "/" => Ok(Self::Div),
+            "%" => Ok(Self::Rem),
+            "^" => Ok(Self::Pow),
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            "==" => Ok(Self::Equal),
+            "!=" => Ok(Self::NotEqual),
+            "<" => Ok(Self::Less),
+            "<=" => Ok(Self::LessEqual),
+            ">" => Ok(Self::Greater),
+            ">=" => Ok(Self::GreaterEqual),
             _ => Err(ParseOpError {}),
         }
     }
 }
 
-impl ToString for Op {
-    fn to_string(&self) -> String {
-        match self {
+impl Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
             Op::Add => "+",
             Op::Sub => "-",
             Op::Mul => "*",
             Op::Div => "/",
-        }
-        .to_owned()
+            Op::Rem => "%",
+            Op::Pow => "^",
+            Op::Min => "min",
+            Op::Max => "max",
+            Op::Equal => "==",
+            Op::NotEqual => "!=",
+            Op::Less => "<",
+            Op::LessEqual => "<=",
+            Op::Greater => ">",
+            Op::GreaterEqual => ">=",
+        })
     }
 }
 
@@ -53,6 +199,58 @@ impl Error for ParseOpError {}
 
 impl Display for ParseOpError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Input not +, -, *, or /")
+        write!(
+            f,
+            "Input not +, -, *, /, %, ^, min, max, ==, !=, <, <=, >, or >="
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::rational::Ratio;
+
+    use super::*;
+
+    #[test]
+    fn calls() {
+        assert_eq!(1, Op::Rem.call(7, 3));
+        assert_eq!(1024, Op::Pow.call(2, 10));
+        assert_eq!(1, Op::Pow.call(5, 0));
+        assert_eq!(3, Op::Min.call(3, 7));
+        assert_eq!(7, Op::Max.call(3, 7));
+        assert_eq!(1, Op::Less.call(3, 7));
+        assert_eq!(0, Op::GreaterEqual.call(3, 7));
+        assert_eq!(1.0, Op::Equal.call(0.5, 0.5));
+        assert_eq!(
+            Ratio::new(9, 4),
+            Op::Pow.call(Ratio::new(3, 2), Ratio::from(2))
+        );
+    }
+
+    #[test]
+    fn checked_calls() {
+        assert_eq!(12, Op::Mul.checked_call(3u64, 4).unwrap());
+        assert!(Op::Mul.checked_call(u64::MAX, 2).is_err());
+        assert!(Op::Sub.checked_call(3u64, 4).is_err());
+        assert!(Op::Div.checked_call(3, 0).is_err());
+        assert!(Op::Rem.checked_call(3, 0).is_err());
+        assert!(Op::Rem.checked_call(i64::MIN, -1).is_err());
+        assert_eq!(1 << 62, Op::Pow.checked_call(2u64, 62).unwrap());
+        assert!(Op::Pow.checked_call(2u64, 64).is_err());
+        assert!(Op::Pow.checked_call(2, -1).is_err());
+        assert!(Op::Pow
+            .checked_call(Ratio::from(4), Ratio::new(1, 2))
+            .is_err());
+    }
+
+    #[test]
+    fn parse_and_display() {
+        for s in [
+            "+", "-", "*", "/", "%", "^", "min", "max", "==", "!=", "<", "<=", ">", ">=",
+        ] {
+            assert_eq!(s, s.parse::<Op>().unwrap().to_string());
+        }
+        assert!("=".parse::<Op>().is_err());
     }
 }
@@ -0,0 +1,134 @@
+use std::fs;
+
+use lib::days;
+
+// Solve a day against its sample_input.txt and compare with the answers given in the puzzle text.
+fn check_sample(day: u8, part1: &str, part2: Option<&str>) {
+    let day = days::get(day).unwrap();
+    let input = fs::read_to_string(day.input_path(true)).unwrap();
+    let solution = day.solution(&input, true);
+
+    assert_eq!(solution.part1(), part1, "day {} part 1", day.number);
+    assert_eq!(
+        solution.part2().as_deref(),
+        part2,
+        "day {} part 2",
+        day.number
+    );
+}
+
+#[test]
+fn day1() {
+    check_sample(1, "24000", Some("45000"));
+}
+
+#[test]
+fn day2() {
+    check_sample(2, "15", Some("12"));
+}
+
+#[test]
+fn day3() {
+    check_sample(3, "157", Some("70"));
+}
+
+#[test]
+fn day4() {
+    check_sample(4, "2", Some("4"));
+}
+
+#[test]
+fn day5() {
+    check_sample(5, "CMZ", Some("MCD"));
+}
+
+#[test]
+fn day6() {
+    check_sample(6, "7", Some("19"));
+}
+
+#[test]
+fn day7() {
+    check_sample(7, "95437", Some("24933642"));
+}
+
+#[test]
+fn day8() {
+    check_sample(8, "21", Some("8"));
+}
+
+#[test]
+fn day9() {
+    check_sample(9, "13", Some("1"));
+}
+
+#[test]
+fn day10() {
+    check_sample(
+        10,
+        "13140",
+        Some(
+            "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....",
+        ),
+    );
+}
+
+#[test]
+fn day11() {
+    check_sample(11, "10605", Some("2713310158"));
+}
+
+#[test]
+fn day12() {
+    check_sample(12, "31", Some("29"));
+}
+
+#[test]
+fn day13() {
+    check_sample(13, "13", Some("140"));
+}
+
+#[test]
+fn day14() {
+    check_sample(14, "24", Some("93"));
+}
+
+#[test]
+fn day15() {
+    check_sample(15, "26", Some("56000011"));
+}
+
+#[test]
+fn day16() {
+    check_sample(16, "1651", Some("1707"));
+}
+
+#[test]
+fn day18() {
+    check_sample(18, "64", Some("58"));
+}
+
+#[test]
+fn day19() {
+    check_sample(19, "33", None);
+}
+
+#[test]
+fn day20() {
+    check_sample(20, "3", Some("1623178306"));
+}
+
+#[test]
+fn day21() {
+    check_sample(21, "152", Some("301"));
+}
+
+#[test]
+fn day22() {
+    check_sample(22, "6032", None);
+}